@@ -0,0 +1,101 @@
+//! Compares traversal and removal throughput of `DlList` (items stored next
+//! to their links) and `SoaDlList` (links and items in separate vectors) for
+//! a large item type.
+//!
+//! Run with `cargo bench`.
+
+extern crate dl_list;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use dl_list::{DlIndex, DlList, SoaDlList};
+
+const LEN: usize = 100_000;
+const ROUNDS: u32 = 20;
+
+// Large enough that a node no longer shares a cache line with its neighbours.
+#[derive(Debug, PartialEq)]
+struct Big([u64; 32]);
+
+impl Big {
+    fn new(i: usize) -> Big {
+        Big([i as u64; 32])
+    }
+}
+
+// A fixed permutation of `0..LEN`, so both layouts see the same scattered
+// removal order.
+fn shuffled() -> Vec<usize> {
+    let mut order: Vec<usize> = (0..LEN).collect();
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+
+    for i in (1..LEN).rev() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+
+        order.swap(i, seed as usize % (i + 1));
+    }
+
+    order
+}
+
+fn report(name: &str, elapsed: Duration, ops: usize) {
+    let per_op = elapsed.as_nanos() as f64 / ops as f64;
+
+    println!("{:<24} {:>10.2?} {:>8.2} ns/op", name, elapsed, per_op);
+}
+
+fn bench_aos(order: &[usize]) {
+    let mut iter_time = Duration::default();
+    let mut remove_time = Duration::default();
+
+    for _ in 0..ROUNDS {
+        let mut list = DlList::new();
+        let indexes: Vec<DlIndex<Big>> = (0..LEN).map(|i| list.push_back(Big::new(i))).collect();
+
+        let start = Instant::now();
+        black_box(list.iter().count());
+        iter_time += start.elapsed();
+
+        let start = Instant::now();
+        for &i in order {
+            black_box(list.remove(indexes[i]));
+        }
+        remove_time += start.elapsed();
+    }
+
+    report("DlList iter", iter_time, LEN * ROUNDS as usize);
+    report("DlList remove", remove_time, LEN * ROUNDS as usize);
+}
+
+fn bench_soa(order: &[usize]) {
+    let mut iter_time = Duration::default();
+    let mut remove_time = Duration::default();
+
+    for _ in 0..ROUNDS {
+        let mut list = SoaDlList::new();
+        let indexes: Vec<DlIndex<Big>> = (0..LEN).map(|i| list.push_back(Big::new(i))).collect();
+
+        let start = Instant::now();
+        black_box(list.iter().count());
+        iter_time += start.elapsed();
+
+        let start = Instant::now();
+        for &i in order {
+            black_box(list.remove(indexes[i]));
+        }
+        remove_time += start.elapsed();
+    }
+
+    report("SoaDlList iter", iter_time, LEN * ROUNDS as usize);
+    report("SoaDlList remove", remove_time, LEN * ROUNDS as usize);
+}
+
+fn main() {
+    let order = shuffled();
+
+    bench_aos(&order);
+    bench_soa(&order);
+}
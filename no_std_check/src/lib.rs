@@ -0,0 +1,51 @@
+//! Builds `dl_list` without its `std` feature, so that anything reaching for
+//! `std` in the list fails to compile here.
+#![no_std]
+
+extern crate alloc;
+extern crate dl_list;
+
+use alloc::vec::Vec;
+
+use dl_list::{ArrayDlList, DlError, DlList};
+
+/// Pushes `items` onto a fresh list and drops every other one, returning what
+/// is left in order.
+pub fn every_other(items: &[u32]) -> Vec<u32> {
+    let mut list = DlList::new();
+    let indexes: Vec<_> = items.iter().map(|&i| list.push_back(i)).collect();
+
+    for index in indexes.into_iter().skip(1).step_by(2) {
+        list.remove(index);
+    }
+
+    list.iter().copied().collect()
+}
+
+/// Fills an inline list until it refuses an item and returns that item.
+pub fn overflow() -> Option<u32> {
+    let mut list: ArrayDlList<u32, 2> = ArrayDlList::new();
+
+    (0..).find_map(|i| list.push_back(i).err())
+}
+
+/// Removes the head twice; the second attempt reports a stale handle.
+pub fn stale() -> Result<u32, DlError> {
+    let mut list = DlList::new();
+    let index = list.push_back(1);
+
+    list.try_remove(index)?;
+    list.try_remove(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_std() {
+        assert_eq!(every_other(&[0, 1, 2, 3, 4]), [0, 2, 4]);
+        assert_eq!(overflow(), Some(2));
+        assert_eq!(stale(), Err(DlError::StaleIndex));
+    }
+}
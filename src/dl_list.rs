@@ -1,13 +1,65 @@
 #![deny(unsafe_code)]
-use std::marker::PhantomData;
+use core::fmt;
+use core::marker::PhantomData;
+
+use alloc::collections::TryReserveError;
+use alloc::vec::Vec;
+
+mod array;
+mod bounded;
+mod branded;
+mod chain;
+mod compact;
+mod entry;
+mod indexed;
+mod ring;
+mod secondary;
+mod sl_list;
+mod soa;
+
+pub use self::array::ArrayDlList;
+pub use self::bounded::{BoundedDlList, Evicted, OverflowPolicy};
+pub use self::branded::{BrandedDlList, BrandedIndex};
+pub use self::compact::{DlIndex32, DlList32};
+pub use self::entry::{Entry, OccupiedEntry, VacantEntry};
+pub use self::indexed::IndexedDlList;
+pub use self::ring::{RingDlList, RingIter};
+pub use self::secondary::DlSecondaryMap;
+pub use self::sl_list::SlList;
+pub use self::soa::SoaDlList;
+
+use self::chain::Chain;
+
+// The slot of the list's sentinel node. It is never stored in `data`: its
+// `next` link is `DlList::head` and its `prev` link is `DlList::tail`, so every
+// node always has two neighbours and links are never empty.
+const SENTINEL: usize = usize::MAX;
 
 #[derive(Debug, PartialEq)]
 pub struct DlList<T> {
-    data: Vec<DlEntry<T>>,
+    data: Vec<DlEntry<OcEntry<T>>>,
     next_free: Option<usize>,
-    head: Option<usize>,
-    tail: Option<usize>,
+    head: usize,
+    tail: usize,
     rm_cnt: usize,
+    len: usize,
+    // The most slots `data` may grow to; `usize::MAX` when uncapped.
+    max_slots: usize,
+    reuse: ReusePolicy,
+    // How many free slots may pile up at the end of `data` before they are
+    // dropped; `usize::MAX` when they never are.
+    trim_threshold: usize,
+    // The end of the free chain; only kept up to date when freed slots are
+    // queued rather than stacked.
+    last_free: Option<usize>,
+    // The operation count at which each free slot was freed; only kept up to
+    // date for `ReusePolicy::Quarantine`.
+    freed_at: Vec<usize>,
+    list_id: Provenance,
+    // The lists this one was mapped from, directly or through other mapped
+    // lists. Their handles are accepted until this list allocates a slot,
+    // after which it could issue the same one as any of them.
+    origins: Provenance<Vec<ListId>>,
 }
 
 impl<T> Default for DlList<T> {
@@ -15,9 +67,17 @@ impl<T> Default for DlList<T> {
         DlList {
             data: Default::default(),
             next_free: Default::default(),
-            head: Default::default(),
-            tail: Default::default(),
+            head: SENTINEL,
+            tail: SENTINEL,
             rm_cnt: Default::default(),
+            len: Default::default(),
+            max_slots: usize::MAX,
+            reuse: ReusePolicy::Lifo,
+            trim_threshold: usize::MAX,
+            last_free: Default::default(),
+            freed_at: Default::default(),
+            list_id: Provenance(ListId::new()),
+            origins: Provenance(Vec::new()),
         }
     }
 }
@@ -25,167 +85,317 @@ impl<T> Default for DlList<T> {
 impl<T> DlList<T>
 where
     T: PartialEq,
-    T: std::fmt::Debug,
+    T: core::fmt::Debug,
 {
     #[allow(dead_code)]
     pub fn new() -> DlList<T> {
         Self::default()
     }
 
+    /// Creates an empty list with room for `capacity` items before it
+    /// reallocates.
     #[allow(dead_code)]
-    pub fn head(&self) -> Option<&T> {
-        let index = self.head?;
+    pub fn with_capacity(capacity: usize) -> DlList<T> {
+        DlList {
+            data: Vec::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
 
-        self.data.get(index).and_then(|e| match e {
-            DlEntry::Free { .. } => None,
-            DlEntry::Occupied(e) => Some(&e.item),
-        })
+    /// Creates an empty list that never holds more than `max_slots` slots.
+    /// `try_push_*` hand the item back once the cap is reached, while the
+    /// other inserting methods panic.
+    #[allow(dead_code)]
+    pub fn with_max_slots(max_slots: usize) -> DlList<T> {
+        DlList {
+            max_slots,
+            ..Self::default()
+        }
     }
 
+    /// Creates an empty list that reuses freed slots according to `reuse`.
     #[allow(dead_code)]
-    pub fn head_index(&self) -> Option<DlIndex<T>> {
-        let index = self.head?;
+    pub fn with_reuse_policy(reuse: ReusePolicy) -> DlList<T> {
+        DlList {
+            reuse,
+            ..Self::default()
+        }
+    }
 
-        self.data.get(index).and_then(|e| match e {
-            DlEntry::Free { .. } => None,
-            DlEntry::Occupied(e) => Some(DlIndex::new(index, e.rm_cnt)),
-        })
+    #[allow(dead_code)]
+    pub fn reuse_policy(&self) -> ReusePolicy {
+        self.reuse
     }
 
+    /// Switches to reusing freed slots according to `reuse`. Slots that are
+    /// already free are requeued in slot order and, under `Quarantine`,
+    /// start their quarantine now. O(slots).
     #[allow(dead_code)]
-    pub fn tail_index(&self) -> Option<DlIndex<T>> {
-        let index = self.tail?;
+    pub fn set_reuse_policy(&mut self, reuse: ReusePolicy) {
+        self.reuse = reuse;
+        self.next_free = None;
+        self.last_free = None;
+        self.freed_at.clear();
 
-        self.data.get(index).and_then(|e| match e {
-            DlEntry::Free { .. } => None,
-            DlEntry::Occupied(e) => Some(DlIndex::new(index, e.rm_cnt)),
-        })
+        let requeue = |list: &mut Self, index: usize| {
+            if let DlEntry::Free { next_free } = &mut list.data[index] {
+                *next_free = None;
+
+                list.push_free(index);
+            }
+        };
+
+        // Stacks and the sorted chain are built back to front, queues front
+        // to back.
+        match reuse {
+            ReusePolicy::Lifo | ReusePolicy::LowestIndex => {
+                for index in (0..self.data.len()).rev() {
+                    requeue(self, index);
+                }
+            }
+            ReusePolicy::Fifo | ReusePolicy::Quarantine(_) => {
+                for index in 0..self.data.len() {
+                    requeue(self, index);
+                }
+            }
+        }
     }
 
+    /// Creates an empty list that drops the free slots at the end of its
+    /// storage once there are `threshold` of them and they make up at least
+    /// half of all free slots. Trimming walks the free chain, which the
+    /// second condition amortizes over the slots dropped; while the storage
+    /// ends in free slots, each removal also walks up to
+    /// O(max(threshold, free slots / 2)) of them to measure the run.
     #[allow(dead_code)]
-    pub fn push_back(&mut self, item: T) -> DlIndex<T> {
-        if self.head.is_none() {
-            let rm_cnt = self.rm_cnt;
+    pub fn with_trim_threshold(threshold: usize) -> DlList<T> {
+        DlList {
+            trim_threshold: threshold,
+            ..Self::default()
+        }
+    }
 
-            let index = if let Some(index) = self.next_free {
-                match self.data[index] {
-                    DlEntry::Occupied { .. } => panic!("list corrupted!"),
-                    DlEntry::Free { next_free } => self.next_free = next_free,
-                }
+    #[allow(dead_code)]
+    pub fn trim_threshold(&self) -> Option<usize> {
+        match self.trim_threshold {
+            usize::MAX => None,
+            threshold => Some(threshold),
+        }
+    }
 
-                self.data[index] = DlEntry::Occupied(OcEntry {
-                    item,
-                    next: None,
-                    prev: None,
-                    rm_cnt,
-                });
-
-                index
-            } else {
-                let index = self.data.len();
-
-                self.data.push(DlEntry::Occupied(OcEntry {
-                    item,
-                    next: None,
-                    prev: None,
-                    rm_cnt,
-                }));
-
-                index
-            };
+    /// Starts trimming as `with_trim_threshold` does, or stops if `threshold`
+    /// is `None`. Trims right away if the storage already qualifies.
+    #[allow(dead_code)]
+    pub fn set_trim_threshold(&mut self, threshold: Option<usize>) {
+        self.trim_threshold = threshold.unwrap_or(usize::MAX);
+
+        self.auto_trim();
+    }
 
-            self.tail = Some(index);
-            self.head = Some(index);
+    /// Drops every free slot at the end of the storage and returns how many
+    /// were dropped. Live handles are untouched; the storage's capacity is
+    /// kept. O(free slots).
+    #[allow(dead_code)]
+    pub fn trim(&mut self) -> usize {
+        let trailing = self.trailing_free_slots();
+        let len = self.data.len() - trailing;
 
-            return DlIndex::new(index, rm_cnt);
+        if trailing == 0 {
+            return 0;
         }
 
-        let tail_index = self.tail.unwrap();
+        // Rethread the free chain through the slots that are kept.
+        let mut next = self.next_free;
+        let mut last = None;
+
+        self.next_free = None;
+
+        while let Some(slot) = next {
+            next = *self.free_link_mut(slot);
 
-        let position = if let Some(position) = self.next_free {
-            match self.data[position] {
-                DlEntry::Occupied { .. } => panic!("list corrupted!"),
-                DlEntry::Free { next_free } => self.next_free = next_free,
+            if slot >= len {
+                continue;
             }
 
-            self.data[position] = DlEntry::Occupied(OcEntry {
-                item,
-                next: None,
-                prev: Some(tail_index),
-                rm_cnt: self.rm_cnt,
-            });
+            match last {
+                Some(last) => *self.free_link_mut(last) = Some(slot),
+                None => self.next_free = Some(slot),
+            }
 
-            position
-        } else {
-            let position = self.data.len();
+            last = Some(slot);
+        }
 
-            self.data.push(DlEntry::Occupied(OcEntry {
-                item,
-                next: None,
-                prev: Some(tail_index),
-                rm_cnt: self.rm_cnt,
-            }));
+        if let Some(last) = last {
+            *self.free_link_mut(last) = None;
+        }
 
-            position
-        };
+        if let ReusePolicy::Fifo | ReusePolicy::Quarantine(_) = self.reuse {
+            self.last_free = last;
+        }
 
-        let new_index = DlIndex::new(position, self.rm_cnt);
+        self.data.truncate(len);
+        self.freed_at.truncate(len);
 
-        match &mut self.data[tail_index] {
-            DlEntry::Free { .. } => panic!("list corrupted!"),
-            DlEntry::Occupied(e) => e.next = Some(new_index.index),
+        trailing
+    }
+
+    /// Number of slots in the storage that hold no item.
+    #[allow(dead_code)]
+    pub fn free_slots(&self) -> usize {
+        self.data.len() - self.len
+    }
+
+    /// Number of free slots at the end of the storage, i.e. what `trim`
+    /// would drop.
+    #[allow(dead_code)]
+    pub fn trailing_free_slots(&self) -> usize {
+        self.data
+            .iter()
+            .rev()
+            .take_while(|e| matches!(e, DlEntry::Free { .. }))
+            .count()
+    }
+
+    /// A snapshot of the list's memory use. Walks the free chain, so it costs
+    /// O(free slots).
+    #[allow(dead_code)]
+    pub fn stats(&self) -> DlStats {
+        let mut free_chain_len = 0;
+        let mut next = self.next_free;
+
+        while let Some(slot) = next {
+            free_chain_len += 1;
+            next = match self.data[slot] {
+                DlEntry::Occupied(_) => panic!("list corrupted!"),
+                DlEntry::Free { next_free } => next_free,
+            };
+        }
+
+        DlStats {
+            len: self.len,
+            free_slots: self.free_slots(),
+            free_chain_len,
+            slots: self.data.len(),
+            capacity: self.data.capacity(),
+            bytes_per_node: core::mem::size_of::<DlEntry<OcEntry<T>>>(),
+            generation: self.rm_cnt,
+            fragmentation: self.fragmentation(),
+            highest_occupied: self
+                .data
+                .iter()
+                .rposition(|e| matches!(e, DlEntry::Occupied(_))),
+            // Every removal bumps the generation, and every item ever
+            // inserted is either still here or was removed.
+            total_inserts: self.len + self.rm_cnt,
+            total_removes: self.rm_cnt,
         }
+    }
 
-        self.tail = Some(position);
+    /// Share of the storage's slots that hold no item, from 0.0 (dense) to
+    /// 1.0 (nothing but free slots).
+    #[allow(dead_code)]
+    pub fn fragmentation(&self) -> f64 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
 
-        new_index
+        self.free_slots() as f64 / self.data.len() as f64
     }
 
     #[allow(dead_code)]
-    pub fn push_front(&mut self, item: T) -> DlIndex<T> {
-        if self.head.is_none() {
-            return self.push_back(item);
+    pub fn max_slots(&self) -> Option<usize> {
+        match self.max_slots {
+            usize::MAX => None,
+            max_slots => Some(max_slots),
         }
+    }
+
+    /// Caps the storage at `max_slots` slots, or lifts the cap if it is
+    /// `None`. Slots past a new cap are kept, but the storage no longer
+    /// grows.
+    #[allow(dead_code)]
+    pub fn set_max_slots(&mut self, max_slots: Option<usize>) {
+        self.max_slots = max_slots.unwrap_or(usize::MAX);
+    }
 
-        let head_index = self.head.unwrap();
+    /// Number of items the list can hold without reallocating.
+    #[allow(dead_code)]
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
 
-        let position = if let Some(position) = self.next_free {
-            match self.data[position] {
-                DlEntry::Occupied { .. } => panic!("list corrupted!"),
-                DlEntry::Free { next_free } => self.next_free = next_free,
-            }
+    /// Makes room for at least `additional` more items, counting slots that
+    /// are already free. Never reserves past `max_slots`.
+    #[allow(dead_code)]
+    pub fn reserve(&mut self, additional: usize) {
+        let additional = self.slots_needed(additional);
 
-            self.data[position] = DlEntry::Occupied(OcEntry {
-                item,
-                next: Some(head_index),
-                prev: None,
-                rm_cnt: self.rm_cnt,
-            });
+        self.data.reserve(additional);
+    }
 
-            position
-        } else {
-            let position = self.data.len();
+    /// Like `reserve`, but reports allocation failure instead of aborting.
+    #[allow(dead_code)]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let additional = self.slots_needed(additional);
 
-            self.data.push(DlEntry::Occupied(OcEntry {
-                item,
-                next: Some(head_index),
-                prev: None,
-                rm_cnt: self.rm_cnt,
-            }));
+        self.data.try_reserve(additional)
+    }
 
-            position
-        };
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
 
-        let new_index = DlIndex::new(position, self.rm_cnt);
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
 
-        match &mut self.data[head_index] {
-            DlEntry::Free { .. } => panic!("list corrupted!"),
-            DlEntry::Occupied(e) => e.prev = Some(new_index.index),
-        }
+    #[allow(dead_code)]
+    pub fn head(&self) -> Option<&T> {
+        self.node(self.head).map(|e| &e.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn head_index(&self) -> Option<DlIndex<T>> {
+        self.handle(self.head)
+    }
+
+    #[allow(dead_code)]
+    pub fn tail_index(&self) -> Option<DlIndex<T>> {
+        self.handle(self.tail)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> DlIndex<T> {
+        let tail = self.tail;
+
+        self.link_between(tail, SENTINEL, item)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> DlIndex<T> {
+        let head = self.head;
+
+        self.link_between(SENTINEL, head, item)
+    }
+
+    /// Like `push_back`, but hands `item` back instead of aborting when the
+    /// list cannot grow, whether from allocation failure or `max_slots`.
+    #[allow(dead_code)]
+    pub fn try_push_back(&mut self, item: T) -> Result<DlIndex<T>, T> {
+        let tail = self.tail;
+
+        self.try_link_between(tail, SENTINEL, item)
+    }
 
-        self.head = Some(position);
+    /// Like `push_front`, but hands `item` back instead of aborting when the
+    /// list cannot grow, whether from allocation failure or `max_slots`.
+    #[allow(dead_code)]
+    pub fn try_push_front(&mut self, item: T) -> Result<DlIndex<T>, T> {
+        let head = self.head;
 
-        new_index
+        self.try_link_between(SENTINEL, head, item)
     }
 
     #[allow(dead_code)]
@@ -195,121 +405,260 @@ where
 
     #[allow(dead_code)]
     pub fn get(&self, index: DlIndex<T>) -> Option<&T> {
-        match self.data.get(index.index)? {
-            DlEntry::Occupied(e) if e.rm_cnt == index.rm_cnt => Some(&e.item),
-            _ => None,
-        }
+        self.try_get(index).ok()
     }
 
+    /// Like `get`, but tells a stale handle apart from one that was issued
+    /// by a different list.
     #[allow(dead_code)]
-    pub fn next_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
-        match self.data.get(index.index)? {
-            DlEntry::Occupied(e) if e.rm_cnt == index.rm_cnt => {
-                match e.next {
-                    Some(index) => match self.data.get(index)? {
-                        DlEntry::Occupied(e) => Some(DlIndex::new(index, e.rm_cnt)),
-                        _ => panic!("list corrupted!"),
-                    },
-                    _ => None, 
-                }
+    pub fn try_get(&self, index: DlIndex<T>) -> Result<&T, DlError> {
+        Ok(&self.check(index)?.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, index: DlIndex<T>) -> Option<&mut T> {
+        Some(&mut self.check_mut(index).ok()?.item)
+    }
+
+    /// Replaces the item at `index`, returning the old one. The slot, links
+    /// and generation are untouched, so the handle stays valid.
+    #[allow(dead_code)]
+    pub fn replace(&mut self, index: DlIndex<T>, item: T) -> Result<T, DlError> {
+        Ok(core::mem::replace(&mut self.check_mut(index)?.item, item))
+    }
+
+    /// Exchanges the items stored at `a` and `b` without relinking, so each
+    /// handle now addresses the other's former item at its own position.
+    #[allow(dead_code)]
+    pub fn swap_values(&mut self, a: DlIndex<T>, b: DlIndex<T>) -> Result<(), DlError> {
+        self.check(a)?;
+        self.check(b)?;
+
+        if a.index == b.index {
+            return Ok(());
+        }
+
+        let (low, high) = (a.index.min(b.index), a.index.max(b.index));
+        let (left, right) = self.data.split_at_mut(high);
+
+        match (&mut left[low], &mut right[0]) {
+            (DlEntry::Occupied(a), DlEntry::Occupied(b)) => {
+                core::mem::swap(&mut a.item, &mut b.item)
             }
-            _ => None, 
+            _ => panic!("list corrupted!"),
         }
+
+        Ok(())
     }
 
+    /// Runs `f` on the item at `index` in place, returning its result.
     #[allow(dead_code)]
-    pub fn prev_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
-        match self.data.get(index.index)? {
-            DlEntry::Occupied(e) if e.rm_cnt == index.rm_cnt => {
-                match e.prev {
-                    Some(index) => match self.data.get(index)? {
-                        DlEntry::Occupied(e) => Some(DlIndex::new(index, e.rm_cnt)),
-                        _ => panic!("list corrupted!"),
-                    },
-                    _ => None,
-                }
-            }
-            _ => None,
+    pub fn update<R, F>(&mut self, index: DlIndex<T>, f: F) -> Result<R, DlError>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        Ok(f(&mut self.check_mut(index)?.item))
+    }
+
+    /// Builds a list of `f(item)` with exactly the same slots, free chain and
+    /// generations, so every handle into this list addresses the
+    /// corresponding item of the new one after `DlIndex::cast`. `f` is called
+    /// in slot order, not list order.
+    ///
+    /// The new list is a list of its own, but until it first allocates a slot
+    /// it accepts cast handles from this one and from every list this one
+    /// still accepts handles from.
+    #[allow(dead_code)]
+    pub fn map<U, F>(self, mut f: F) -> DlList<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        let origins = self.derived_origins();
+
+        DlList {
+            data: self
+                .data
+                .into_iter()
+                .map(|entry| entry.map(|e| e.map(&mut f)))
+                .collect(),
+            next_free: self.next_free,
+            head: self.head,
+            tail: self.tail,
+            rm_cnt: self.rm_cnt,
+            len: self.len,
+            max_slots: self.max_slots,
+            reuse: self.reuse,
+            trim_threshold: self.trim_threshold,
+            last_free: self.last_free,
+            freed_at: self.freed_at,
+            list_id: Provenance(ListId::new()),
+            origins: Provenance(origins),
         }
     }
 
+    /// Like `map`, but borrows this list so both can be used side by side.
     #[allow(dead_code)]
-    pub fn remove(&mut self, index: DlIndex<T>) -> Option<T> {
-        let head_index = self.head?;
-        let tail_index = self.tail?;
+    pub fn map_ref<U, F>(&self, mut f: F) -> DlList<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        DlList {
+            data: self
+                .data
+                .iter()
+                .map(|entry| entry.as_ref().map(|e| e.as_ref().map(&mut f)))
+                .collect(),
+            next_free: self.next_free,
+            head: self.head,
+            tail: self.tail,
+            rm_cnt: self.rm_cnt,
+            len: self.len,
+            max_slots: self.max_slots,
+            reuse: self.reuse,
+            trim_threshold: self.trim_threshold,
+            last_free: self.last_free,
+            freed_at: self.freed_at.clone(),
+            list_id: Provenance(ListId::new()),
+            origins: Provenance(self.derived_origins()),
+        }
+    }
 
-        let (prev_index, index, next_index) = match self.data.get(index.index)? {
-            DlEntry::Free { .. } => return None,
-            DlEntry::Occupied(e) => {
-                if index.rm_cnt != e.rm_cnt {
-                    return None;
-                }
+    /// Looks `index` up once, for read-modify-delete flows that would
+    /// otherwise validate the handle several times.
+    #[allow(dead_code)]
+    pub fn entry(&mut self, index: DlIndex<T>) -> Entry<'_, T> {
+        Entry::new(self, index)
+    }
 
-                (e.prev, index.index, e.next)
-            }
-        };
+    /// Moves `index` to the head of the list. O(1).
+    #[allow(dead_code)]
+    pub fn move_to_front(&mut self, index: DlIndex<T>) -> Result<(), DlError> {
+        self.check(index)?;
 
-        let removed = std::mem::replace(
-            &mut self.data[index],
-            DlEntry::Free {
-                next_free: self.next_free,
-            },
-        );
+        self.unlink(index.index);
 
-        self.next_free = Some(index);
+        let head = self.head;
+        self.set_links(index.index, SENTINEL, head);
 
-        self.rm_cnt += 1;
+        Ok(())
+    }
 
-        if (index == head_index) && (index == tail_index) {
-            self.head = None;
-            self.tail = None;
-        } else if index == head_index {
-            let next = match &mut self.data[next_index.unwrap()] {
-                DlEntry::Free { .. } => panic!("list corrupted!"),
-                DlEntry::Occupied(e) => e,
-            };
+    /// Moves `index` to the tail of the list. O(1).
+    #[allow(dead_code)]
+    pub fn move_to_back(&mut self, index: DlIndex<T>) -> Result<(), DlError> {
+        self.check(index)?;
 
-            next.prev = None;
-            self.head = next_index;
+        self.unlink(index.index);
 
-        } else if index == tail_index {
-            let prev = match &mut self.data[prev_index.unwrap()] {
-                DlEntry::Free { .. } => panic!("list corrupted!"),
-                DlEntry::Occupied(e) => e,
-            };
+        let tail = self.tail;
+        self.set_links(index.index, tail, SENTINEL);
 
-            prev.next = None;
-            self.tail = prev_index;
+        Ok(())
+    }
 
-        } else if index != head_index && index != tail_index {
-            {
-                let next = match &mut self.data[next_index.unwrap()] {
-                    DlEntry::Free { .. } => panic!("list corrupted!"),
-                    DlEntry::Occupied(e) => e,
-                };
+    /// Turns an id from `DlIndex::to_raw` back into a handle, provided it
+    /// still names a live item of this list.
+    #[allow(dead_code)]
+    pub fn index_from_raw(&self, raw: u64) -> Result<DlIndex<T>, DlError> {
+        let index = (raw & 0xffff_ffff) as usize;
+        let generation = (raw >> 32) as u32;
 
-                next.prev = prev_index;
+        match self.data.get(index) {
+            Some(DlEntry::Occupied(e)) if e.rm_cnt as u32 == generation => {
+                Ok(DlIndex::new(index, e.rm_cnt, self.list_id.0))
             }
+            _ => Err(DlError::StaleIndex),
+        }
+    }
+
+    /// Parses the `Display` form of a handle, provided it still names a
+    /// live item of this list.
+    #[allow(dead_code)]
+    pub fn index_from_str(&self, s: &str) -> Result<DlIndex<T>, DlError> {
+        let mut parts = s.splitn(2, 'v');
+
+        let index = parts.next().and_then(|p| p.parse().ok());
+        let rm_cnt = parts.next().and_then(|p| p.parse().ok());
+
+        match (index, rm_cnt) {
+            (Some(index), Some(rm_cnt)) => {
+                let index = DlIndex::new(index, rm_cnt, self.list_id.0);
+
+                self.check(index)?;
 
-            {
-                let prev = match &mut self.data[prev_index.unwrap()] {
-                    DlEntry::Free { .. } => panic!("list corrupted!"),
-                    DlEntry::Occupied(e) => e,
-                };
-                prev.next = next_index;
+                Ok(index)
             }
+            _ => Err(DlError::Malformed),
         }
+    }
 
-        match removed {
-            DlEntry::Free { .. } => panic!("list corrupted!"),
-            DlEntry::Occupied(e) => Some(e.item),
+    /// Returns `true` if `index` refers to an item that is still in the list.
+    #[allow(dead_code)]
+    pub fn contains_index(&self, index: DlIndex<T>) -> bool {
+        self.check(index).is_ok()
+    }
+
+    /// Same as `contains_index`; reads better where a handle is being
+    /// validated before use.
+    #[allow(dead_code)]
+    pub fn is_valid(&self, index: DlIndex<T>) -> bool {
+        self.contains_index(index)
+    }
+
+    /// Explains what `index` currently refers to, for diagnosing stale
+    /// handles.
+    #[allow(dead_code)]
+    pub fn index_status(&self, index: DlIndex<T>) -> IndexStatus {
+        if !self.accepts(index.list_id) {
+            return IndexStatus::Foreign;
+        }
+
+        match self.data.get(index.index) {
+            None => IndexStatus::OutOfBounds,
+            Some(DlEntry::Free { .. }) => IndexStatus::Removed,
+            Some(DlEntry::Occupied(e)) if e.rm_cnt == index.rm_cnt => IndexStatus::Live,
+            Some(DlEntry::Occupied(e)) => IndexStatus::Reused {
+                current_gen: e.rm_cnt,
+            },
         }
     }
 
+    #[allow(dead_code)]
+    pub fn next_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        let next = self.check(index).ok()?.next;
+
+        self.handle(next)
+    }
+
+    #[allow(dead_code)]
+    pub fn prev_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        let prev = self.check(index).ok()?.prev;
+
+        self.handle(prev)
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex<T>) -> Option<T> {
+        self.try_remove(index).ok()
+    }
+
+    /// Like `remove`, but tells a stale handle apart from one that was
+    /// issued by a different list.
+    #[allow(dead_code)]
+    pub fn try_remove(&mut self, index: DlIndex<T>) -> Result<T, DlError> {
+        self.check(index)?;
+
+        let item = self.remove_slot(index.index);
+
+        self.auto_trim();
+
+        Ok(item)
+    }
+
     #[allow(dead_code)]
     pub fn iter(&self) -> impl Iterator<Item = &T> {
         Iter {
-            list: self,
+            slots: &self.data,
             next_index: self.head,
         }
     }
@@ -318,16 +667,12 @@ where
     pub fn index_of(&self, item: &T) -> Option<DlIndex<T>> {
         let mut next = self.head;
 
-        while let Some(index) = next {
-            let ref entry = match &self.data[index] {
-                DlEntry::Free { .. } => panic!("list corrupted!"),
-                DlEntry::Occupied(entry) => entry,
-            };
+        while let Some(entry) = self.node(next) {
             if &entry.item == item {
-                return Some(DlIndex::new(index, entry.rm_cnt));
-            } else {
-                next = entry.next;
+                return self.handle(next);
             }
+
+            next = entry.next;
         }
 
         None
@@ -335,511 +680,2291 @@ where
 
     #[allow(dead_code)]
     pub fn pop_front(&mut self) -> Option<T> {
-        let head_index = self.head?;
+        let head = self.head;
 
-        let (head_index, next_index) = match self.data.get(head_index)? {
-            DlEntry::Free { .. } => return None,
-            DlEntry::Occupied(e) => (head_index, e.next),
-        };
+        self.node(head)?;
 
-        let removed = std::mem::replace(
-            &mut self.data[head_index],
-            DlEntry::Free {
-                next_free: self.next_free,
-            },
-        );
+        let item = self.remove_slot(head);
 
-        self.next_free = Some(head_index);
+        self.auto_trim();
 
-        self.rm_cnt += 1;
+        Some(item)
+    }
 
-        if Some(head_index) == self.tail {
-            self.head = None;
-            self.tail = None;
-        } else {
-            let next = match &mut self.data[next_index.unwrap()] {
-                DlEntry::Free { .. } => panic!("list corrupted!"),
-                DlEntry::Occupied(e) => e,
-            };
+    /// Returns the item at position `pos`, counting from the head.
+    ///
+    /// Walks from whichever end is closer: O(min(pos, len - pos)).
+    #[allow(dead_code)]
+    pub fn nth(&self, pos: usize) -> Option<&T> {
+        let index = self.slot_at(pos)?;
 
-            next.prev = None;
-            self.head = next_index;
-        }
+        Some(&self.occupied(index).item)
+    }
 
-        match removed {
-            DlEntry::Free { .. } => panic!("list corrupted!"),
-            DlEntry::Occupied(e) => Some(e.item),
-        }
+    /// Returns the handle of the item at position `pos`.
+    ///
+    /// Walks from whichever end is closer: O(min(pos, len - pos)).
+    #[allow(dead_code)]
+    pub fn nth_index(&self, pos: usize) -> Option<DlIndex<T>> {
+        let index = self.slot_at(pos)?;
+
+        Some(DlIndex::new(index, self.occupied(index).rm_cnt, self.list_id.0))
     }
-}
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct DlIndex<T> {
-    index: usize,
-    rm_cnt: usize,
-    _marker: PhantomData<T>,
-}
+    /// Returns the position of `index` counting from the head, or `None` if
+    /// the handle is stale.
+    ///
+    /// Walks towards both ends at once and stops at whichever is reached
+    /// first: O(min(pos, len - pos)).
+    #[allow(dead_code)]
+    pub fn position_of(&self, index: DlIndex<T>) -> Option<usize> {
+        self.check(index).ok()?;
 
-impl<T> DlIndex<T> {
-    fn new(index: usize, rm_cnt: usize) -> DlIndex<T> {
-        DlIndex {
-            index,
-            rm_cnt,
-            _marker: PhantomData,
+        Some(self.slot_position(index.index))
+    }
+
+    /// Inserts `item` so that it ends up at position `pos`.
+    ///
+    /// Walks from whichever end is closer: O(min(pos, len - pos)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos > len`.
+    #[allow(dead_code)]
+    pub fn insert_at(&mut self, pos: usize, item: T) -> DlIndex<T> {
+        let len = self.len;
+
+        assert!(
+            pos <= len,
+            "insertion position (is {}) should be <= len (is {})",
+            pos,
+            len
+        );
+
+        match self.slot_at(pos) {
+            Some(index) => self.link_before(index, item),
+            None => self.push_back(item),
         }
     }
-}
 
-#[derive(Debug, PartialEq)]
-enum DlEntry<T> {
-    Free { next_free: Option<usize> },
-    Occupied(OcEntry<T>),
-}
+    /// Inserts `item` directly after `index`.
+    #[allow(dead_code)]
+    pub fn insert_after(&mut self, index: DlIndex<T>, item: T) -> Result<DlIndex<T>, DlError> {
+        self.check(index)?;
 
-#[derive(Debug, PartialEq)]
-struct OcEntry<T> {
-    item: T,
-    next: Option<usize>,
-    prev: Option<usize>,
-    rm_cnt: usize,
-}
+        Ok(self.link_after(index.index, item))
+    }
 
-struct Iter<'a, T>
-where
-    T: 'a,
-{
-    list: &'a DlList<T>,
-    next_index: Option<usize>,
-}
+    /// Like `push_back`, but builds the item from the handle it is about to
+    /// be stored under.
+    #[allow(dead_code)]
+    pub fn push_back_with<F>(&mut self, f: F) -> DlIndex<T>
+    where
+        F: FnOnce(DlIndex<T>) -> T,
+    {
+        self.vacant_entry().push_back_with(f)
+    }
 
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = &'a T;
+    /// Like `push_front`, but builds the item from the handle it is about to
+    /// be stored under.
+    #[allow(dead_code)]
+    pub fn push_front_with<F>(&mut self, f: F) -> DlIndex<T>
+    where
+        F: FnOnce(DlIndex<T>) -> T,
+    {
+        self.vacant_entry().push_front_with(f)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let next_index = self.next_index?;
+    /// Like `insert_after`, but builds the item from the handle it is about
+    /// to be stored under. `f` is not called if `index` is invalid.
+    #[allow(dead_code)]
+    pub fn insert_after_with<F>(&mut self, index: DlIndex<T>, f: F) -> Result<DlIndex<T>, DlError>
+    where
+        F: FnOnce(DlIndex<T>) -> T,
+    {
+        self.vacant_entry().insert_after_with(index, f)
+    }
 
-        match &self.list.data[next_index] {
+    /// Reserves the slot the next inserted item will occupy, exposing its
+    /// handle before the item exists. Nothing is linked until the entry is
+    /// filled; dropping it leaves the list untouched.
+    #[allow(dead_code)]
+    pub fn vacant_entry(&mut self) -> VacantEntry<'_, T> {
+        VacantEntry::new(self)
+    }
+
+    /// Removes and returns the item at position `pos`.
+    ///
+    /// Walks from whichever end is closer: O(min(pos, len - pos)).
+    #[allow(dead_code)]
+    pub fn remove_at(&mut self, pos: usize) -> Option<T> {
+        let index = self.nth_index(pos)?;
+
+        self.remove(index)
+    }
+
+    /// Drops every item and releases the storage. All handles become stale.
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.clear_keep_capacity();
+        self.data = Vec::new();
+    }
+
+    /// Drops every item but keeps the storage's capacity for reuse. All
+    /// handles become stale.
+    #[allow(dead_code)]
+    pub fn clear_keep_capacity(&mut self) {
+        self.data.clear();
+        self.freed_at.clear();
+        self.next_free = None;
+        self.last_free = None;
+        self.head = SENTINEL;
+        self.tail = SENTINEL;
+
+        // Count every dropped item as a removal, so that no slot can come
+        // back with the generation of a handle issued before the clear.
+        self.rm_cnt += self.len;
+        self.len = 0;
+    }
+
+    /// Removes items from the tail until at most `len` are left.
+    #[allow(dead_code)]
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            let tail = self.tail;
+
+            self.remove_slot(tail);
+        }
+
+        self.auto_trim();
+    }
+
+    /// Removes items from the head until at most `len` are left.
+    #[allow(dead_code)]
+    pub fn truncate_front(&mut self, len: usize) {
+        while self.len > len {
+            let head = self.head;
+
+            self.remove_slot(head);
+        }
+
+        self.auto_trim();
+    }
+
+    /// Removes the items from `from` through `to`, in list order, and yields
+    /// them. Items the iterator is not driven to are removed when it is
+    /// dropped.
+    ///
+    /// Fails with `DlError::InvalidRange` if `to` comes before `from`.
+    /// Finding that out walks the range up front.
+    #[allow(dead_code)]
+    pub fn remove_range(
+        &mut self,
+        from: DlIndex<T>,
+        to: DlIndex<T>,
+    ) -> Result<impl Iterator<Item = T> + '_, DlError> {
+        self.check(from)?;
+        self.check(to)?;
+
+        let mut index = from.index;
+
+        while index != to.index {
+            index = self.next_of(index);
+
+            if index == SENTINEL {
+                return Err(DlError::InvalidRange);
+            }
+        }
+
+        Ok(Drain {
+            list: self,
+            next: from.index,
+            last: to.index,
+            done: false,
+        })
+    }
+
+    /// Reverses the order of the list by flipping every node's links.
+    /// Handles stay valid. O(n).
+    #[allow(dead_code)]
+    pub fn reverse(&mut self) {
+        self.reverse_links();
+    }
+
+    /// Rotates the list so that the item at position `n % len` becomes the
+    /// head. O(min(n, len - n)).
+    #[allow(dead_code)]
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        if let Some(index) = self.slot_at(n % self.len) {
+            self.rotate_to_slot(index);
+        }
+    }
+
+    /// Rotates the list so that the last `n % len` items move to the front.
+    /// O(min(n, len - n)).
+    #[allow(dead_code)]
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        self.rotate_left(self.len - n % self.len);
+    }
+
+    /// Rotates the list so that `index` becomes the head, keeping the cyclic
+    /// order of all items. O(1).
+    #[allow(dead_code)]
+    pub fn rotate_to_front(&mut self, index: DlIndex<T>) -> Result<(), DlError> {
+        self.check(index)?;
+
+        self.rotate_to_slot(index.index);
+
+        Ok(())
+    }
+
+    /// Exchanges the positions of `a` and `b` by relinking, so neither value
+    /// moves and both handles stay valid. O(1).
+    #[allow(dead_code)]
+    pub fn swap(&mut self, a: DlIndex<T>, b: DlIndex<T>) -> Result<(), DlError> {
+        self.check(a)?;
+        self.check(b)?;
+
+        self.swap_slots(a.index, b.index);
+
+        Ok(())
+    }
+
+    fn link_after(&mut self, prev: usize, item: T) -> DlIndex<T> {
+        let next = self.next_of(prev);
+
+        self.link_between(prev, next, item)
+    }
+
+    fn link_before(&mut self, next: usize, item: T) -> DlIndex<T> {
+        let prev = self.prev_of(next);
+
+        self.link_between(prev, next, item)
+    }
+
+    fn link_between(&mut self, prev: usize, next: usize, item: T) -> DlIndex<T> {
+        let rm_cnt = self.rm_cnt;
+
+        let index = self.alloc(OcEntry {
+            item,
+            next,
+            prev,
+            rm_cnt,
+        });
+
+        self.relink(index);
+        self.len += 1;
+
+        DlIndex::new(index, rm_cnt, self.list_id.0)
+    }
+
+    fn try_link_between(&mut self, prev: usize, next: usize, item: T) -> Result<DlIndex<T>, T> {
+        // Only a push onto `data` can fail; a free slot is always at hand.
+        if self.reusable_slot().is_none()
+            && (self.data.len() >= self.max_slots || self.data.try_reserve(1).is_err())
+        {
+            return Err(item);
+        }
+
+        Ok(self.link_between(prev, next, item))
+    }
+
+    // The slots `data` has to grow by to take `additional` more items.
+    fn slots_needed(&self, additional: usize) -> usize {
+        let free = match self.reuse {
+            // Only count the slots whose quarantine is already over.
+            ReusePolicy::Quarantine(n) => {
+                let mut free = 0;
+                let mut next = self.next_free;
+
+                while let Some(slot) = next.filter(|&slot| self.released(slot, n)) {
+                    free += 1;
+                    next = match self.data[slot] {
+                        DlEntry::Occupied(_) => panic!("list corrupted!"),
+                        DlEntry::Free { next_free } => next_free,
+                    };
+                }
+
+                free
+            }
+            _ => self.data.len() - self.len,
+        };
+        // A lowered cap may sit below the slots already in use.
+        let room = self.max_slots.saturating_sub(self.data.len());
+
+        additional.saturating_sub(free).min(room)
+    }
+
+    // Unlinks `index` and returns its slot to the free chain.
+    fn remove_slot(&mut self, index: usize) -> T {
+        self.unlink(index);
+
+        let removed = core::mem::replace(&mut self.data[index], DlEntry::Free { next_free: None });
+
+        self.rm_cnt += 1;
+        self.len -= 1;
+
+        self.push_free(index);
+
+        match removed {
             DlEntry::Free { .. } => panic!("list corrupted!"),
-            DlEntry::Occupied(e) => {
-                self.next_index = e.next;
+            DlEntry::Occupied(e) => e.item,
+        }
+    }
+
+    // Trims the storage once the run of trailing free slots has reached
+    // `trim_threshold` and holds at least half of all free slots, so that the
+    // free chain walk in `trim` is paid for by the slots it drops.
+    fn auto_trim(&mut self) {
+        let threshold = self.trim_threshold;
+
+        if threshold == usize::MAX || !matches!(self.data.last(), Some(DlEntry::Free { .. })) {
+            return;
+        }
+
+        let needed = threshold.max(self.free_slots() / 2);
+
+        let trailing = self
+            .data
+            .iter()
+            .rev()
+            .take(needed)
+            .take_while(|e| matches!(e, DlEntry::Free { .. }))
+            .count();
+
+        if trailing >= needed {
+            self.trim();
+        }
+    }
+
+    // Adds the free slot `index` to the free chain where `reuse` wants it.
+    fn push_free(&mut self, index: usize) {
+        match self.reuse {
+            ReusePolicy::Lifo => {
+                *self.free_link_mut(index) = self.next_free;
+                self.next_free = Some(index);
+            }
+            ReusePolicy::Fifo | ReusePolicy::Quarantine(_) => {
+                match self.last_free {
+                    Some(last) => *self.free_link_mut(last) = Some(index),
+                    None => self.next_free = Some(index),
+                }
+
+                self.last_free = Some(index);
+
+                if let ReusePolicy::Quarantine(_) = self.reuse {
+                    self.freed_at.resize(self.data.len(), 0);
+                    self.freed_at[index] = self.ops();
+                }
+            }
+            ReusePolicy::LowestIndex => {
+                let mut prev = None;
+                let mut next = self.next_free;
+
+                while let Some(slot) = next.filter(|&slot| slot < index) {
+                    prev = Some(slot);
+                    next = *self.free_link_mut(slot);
+                }
+
+                *self.free_link_mut(index) = next;
+
+                match prev {
+                    Some(prev) => *self.free_link_mut(prev) = Some(index),
+                    None => self.next_free = Some(index),
+                }
+            }
+        }
+    }
+
+    // The free slot `alloc` will reuse, if any.
+    fn reusable_slot(&self) -> Option<usize> {
+        match self.reuse {
+            // Every slot behind the head of the queue was freed after it. At
+            // the slot cap the head is reused early rather than not at all.
+            ReusePolicy::Quarantine(n) if self.data.len() < self.max_slots => {
+                self.next_free.filter(|&slot| self.released(slot, n))
+            }
+            _ => self.next_free,
+        }
+    }
+
+    // Insertions plus removals so far. Clearing counts as removing every
+    // item, as it does for the generation.
+    fn ops(&self) -> usize {
+        self.len.wrapping_add(self.rm_cnt.wrapping_mul(2))
+    }
+
+    // Whether the free slot `slot` has sat out `n` operations since it was
+    // freed.
+    fn released(&self, slot: usize, n: usize) -> bool {
+        self.ops().wrapping_sub(self.freed_at[slot]) >= n
+    }
+
+    // The slot `alloc` will hand out next.
+    fn next_slot(&self) -> usize {
+        self.reusable_slot().unwrap_or(self.data.len())
+    }
+
+    fn alloc(&mut self, entry: OcEntry<T>) -> usize {
+        self.origins.0.clear();
+
+        if let Some(position) = self.reusable_slot() {
+            self.next_free = *self.free_link_mut(position);
+
+            if self.next_free.is_none() {
+                self.last_free = None;
+            }
+
+            self.data[position] = DlEntry::Occupied(entry);
+
+            position
+        } else {
+            assert!(
+                self.data.len() < self.max_slots,
+                "slot limit (is {}) reached",
+                self.max_slots
+            );
+
+            self.data.push(DlEntry::Occupied(entry));
+
+            self.data.len() - 1
+        }
+    }
+
+    // The ids a list mapped from this one accepts handles from.
+    fn derived_origins(&self) -> Vec<ListId> {
+        let mut origins = self.origins.0.clone();
+
+        origins.push(self.list_id.0);
+
+        origins
+    }
+
+    fn free_link_mut(&mut self, index: usize) -> &mut Option<usize> {
+        match &mut self.data[index] {
+            DlEntry::Occupied(_) => panic!("list corrupted!"),
+            DlEntry::Free { next_free } => next_free,
+        }
+    }
+
+}
+
+impl<T> Chain<T> for DlList<T> {
+    type Node = OcEntry<T>;
+
+    fn slots(&self) -> &[DlEntry<OcEntry<T>>] {
+        &self.data
+    }
+
+    fn slots_mut(&mut self) -> &mut [DlEntry<OcEntry<T>>] {
+        &mut self.data
+    }
+
+    fn ends(&self) -> (usize, usize) {
+        (self.head, self.tail)
+    }
+
+    fn ends_mut(&mut self) -> (&mut usize, &mut usize) {
+        (&mut self.head, &mut self.tail)
+    }
+
+    fn count(&self) -> usize {
+        self.len
+    }
+
+    fn id(&self) -> ListId {
+        self.list_id.0
+    }
+
+    fn accepts(&self, id: ListId) -> bool {
+        id == self.list_id.0 || self.origins.0.contains(&id)
+    }
+}
+
+#[derive(Debug)]
+pub struct DlIndex<T> {
+    index: usize,
+    rm_cnt: usize,
+    list_id: ListId,
+    _marker: PhantomData<T>,
+}
+
+// Implemented by hand so that handles are `Copy`, `Eq` and `Hash` regardless
+// of `T`.
+impl<T> Clone for DlIndex<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DlIndex<T> {}
+
+impl<T> PartialEq for DlIndex<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.rm_cnt == other.rm_cnt && self.list_id == other.list_id
+    }
+}
+
+impl<T> Eq for DlIndex<T> {}
+
+impl<T> core::hash::Hash for DlIndex<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.rm_cnt.hash(state);
+        self.list_id.hash(state);
+    }
+}
+
+impl<T> DlIndex<T> {
+    fn new(index: usize, rm_cnt: usize, list_id: ListId) -> DlIndex<T> {
+        DlIndex {
+            index,
+            rm_cnt,
+            list_id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Packs the handle into a `u64` for passing across APIs: the slot in the
+    /// low 32 bits and the generation in the high 32 bits. Generations past
+    /// `u32::MAX` wrap, so an id is only ambiguous between items that were
+    /// stored in the same slot 2^32 removals apart.
+    ///
+    /// The id does not record which list issued it; turn it back into a
+    /// handle with `DlList::index_from_raw`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slot does not fit in 32 bits.
+    #[allow(dead_code)]
+    pub fn to_raw(self) -> u64 {
+        assert!(
+            self.index <= u32::MAX as usize,
+            "slot {} does not fit in a raw index",
+            self.index
+        );
+
+        ((self.rm_cnt as u32 as u64) << 32) | self.index as u64
+    }
+
+    /// Reinterprets this handle for a list produced by `DlList::map` or
+    /// `DlList::map_ref`. The result is only accepted by the list that issued
+    /// this handle and by lists mapped from it that have not allocated a slot
+    /// since.
+    #[allow(dead_code)]
+    pub fn cast<U>(self) -> DlIndex<U> {
+        DlIndex::new(self.index, self.rm_cnt, self.list_id)
+    }
+}
+
+// Identifies the list a `DlIndex` was issued by. The id is only stored in
+// debug builds or with the `provenance` feature, and only on targets with
+// pointer-sized atomics to hand it out; otherwise this is a zero-sized type
+// and every id compares equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ListId {
+    #[cfg(all(
+        any(debug_assertions, feature = "provenance"),
+        target_has_atomic = "ptr"
+    ))]
+    id: usize,
+}
+
+impl ListId {
+    #[cfg(all(
+        any(debug_assertions, feature = "provenance"),
+        target_has_atomic = "ptr"
+    ))]
+    fn new() -> ListId {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        ListId {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    #[cfg(not(all(
+        any(debug_assertions, feature = "provenance"),
+        target_has_atomic = "ptr"
+    )))]
+    fn new() -> ListId {
+        ListId {}
+    }
+}
+
+// A list's own identity, and whatever other ids it accepts handles under.
+// It compares equal to any other, so that two lists with the same contents
+// are equal whichever lists they are.
+#[derive(Debug, Clone, Copy)]
+struct Provenance<P = ListId>(P);
+
+impl<P> PartialEq for Provenance<P> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DlError {
+    /// The handle was issued by a different list.
+    ForeignIndex,
+    /// The handle's slot has been removed, and possibly reused since.
+    StaleIndex,
+    /// A string could not be parsed as a handle.
+    Malformed,
+    /// The end of a range comes before its start.
+    InvalidRange,
+}
+
+impl fmt::Display for DlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DlError::ForeignIndex => write!(f, "index belongs to a different list"),
+            DlError::StaleIndex => write!(f, "index refers to a removed item"),
+            DlError::Malformed => write!(f, "malformed index"),
+            DlError::InvalidRange => write!(f, "range end precedes its start"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DlError {}
+
+// Formats as `<slot>v<generation>`, which `DlList::index_from_str` parses.
+impl<T> fmt::Display for DlIndex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.rm_cnt)
+    }
+}
+
+/// Which free slot a `DlList` fills next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReusePolicy {
+    /// The most recently freed slot. The default.
+    Lifo,
+    /// The least recently freed slot, so a stale handle's slot stays free
+    /// for as long as possible.
+    Fifo,
+    /// The lowest free slot, keeping the occupied slots packed at the front
+    /// of the storage. Freeing a slot walks the free chain.
+    LowestIndex,
+    /// Like `Fifo`, but a freed slot is only reused once `n` more insertions
+    /// or removals have happened since; until then the list grows instead.
+    /// Once the list has reached its `max_slots`, the oldest free slot is
+    /// reused early.
+    Quarantine(usize),
+}
+
+/// Memory use of a `DlList`, as reported by `DlList::stats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DlStats {
+    /// Items in the list.
+    pub len: usize,
+    /// Slots in the storage that hold no item.
+    pub free_slots: usize,
+    /// Slots threaded through the free chain; equals `free_slots` unless the
+    /// list is corrupted.
+    pub free_chain_len: usize,
+    /// Slots in the storage, occupied or free.
+    pub slots: usize,
+    /// Slots the storage can hold without reallocating.
+    pub capacity: usize,
+    /// Size of one slot, including the item, links and generation.
+    pub bytes_per_node: usize,
+    /// The generation the next inserted item will get.
+    pub generation: usize,
+    /// `free_slots / slots`, or 0.0 for empty storage.
+    pub fragmentation: f64,
+    /// The last occupied slot, if any; `trim` would keep everything up to it.
+    pub highest_occupied: Option<usize>,
+    /// Items ever inserted.
+    pub total_inserts: usize,
+    /// Items ever removed.
+    pub total_removes: usize,
+}
+
+/// What a `DlIndex` refers to, as reported by `DlList::index_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexStatus {
+    /// The item the handle was issued for is still in the list.
+    Live,
+    /// The item was removed and its slot is currently free.
+    Removed,
+    /// The item was removed and its slot now holds a newer item, inserted
+    /// when the list's generation counter was `current_gen`.
+    Reused { current_gen: usize },
+    /// The slot is past the end of the list's storage.
+    OutOfBounds,
+    /// The handle was issued by a different list.
+    Foreign,
+}
+
+// A slab slot, shared by the list variants that differ only in what an
+// occupied node stores and how a free slot links to the next one.
+#[derive(Debug, PartialEq)]
+enum DlEntry<E, L = Option<usize>> {
+    Free { next_free: L },
+    Occupied(E),
+}
+
+#[derive(Debug, PartialEq)]
+struct OcEntry<T> {
+    item: T,
+    next: usize,
+    prev: usize,
+    rm_cnt: usize,
+}
+
+impl<E, L: Copy> DlEntry<E, L> {
+    fn as_ref(&self) -> DlEntry<&E, L> {
+        match self {
+            DlEntry::Free { next_free } => DlEntry::Free {
+                next_free: *next_free,
+            },
+            DlEntry::Occupied(e) => DlEntry::Occupied(e),
+        }
+    }
+}
+
+impl<E, L> DlEntry<E, L> {
+    fn map<F, U>(self, f: F) -> DlEntry<U, L>
+    where
+        F: FnOnce(E) -> U,
+    {
+        match self {
+            DlEntry::Free { next_free } => DlEntry::Free { next_free },
+            DlEntry::Occupied(e) => DlEntry::Occupied(f(e)),
+        }
+    }
+}
+
+impl<T> OcEntry<T> {
+    fn as_ref(&self) -> OcEntry<&T> {
+        OcEntry {
+            item: &self.item,
+            next: self.next,
+            prev: self.prev,
+            rm_cnt: self.rm_cnt,
+        }
+    }
+
+    fn map<F, U>(self, f: F) -> OcEntry<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        OcEntry {
+            item: f(self.item),
+            next: self.next,
+            prev: self.prev,
+            rm_cnt: self.rm_cnt,
+        }
+    }
+}
+
+struct Iter<'a, T>
+where
+    T: 'a,
+{
+    slots: &'a [DlEntry<OcEntry<T>>],
+    next_index: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.slots.get(self.next_index)? {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => {
+                self.next_index = e.next;
+
+                Some(&e.item)
+            }
+        }
+    }
+}
+
+struct Drain<'a, T>
+where
+    T: 'a,
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    list: &'a mut DlList<T>,
+    next: usize,
+    last: usize,
+    done: bool,
+}
+
+impl<'a, T> Iterator for Drain<'a, T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let index = self.next;
+
+        self.done = index == self.last;
+        self.next = self.list.next_of(index);
+
+        Some(self.list.remove_slot(index))
+    }
+}
+
+impl<'a, T> Drop for Drain<'a, T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+        self.list.auto_trim();
+    }
+}
+
+impl<T> core::ops::Index<DlIndex<T>> for DlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.try_get(index).unwrap()
+    }
+}
+
+// Start Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_list() {
+        let _list: DlList<i32> = DlList::new();
+    }
+
+    #[test]
+    fn eq_ignores_list_id() {
+        let mut a = DlList::new();
+        let mut b = DlList::new();
+
+        a.push_back(1);
+        b.push_back(1);
+
+        assert_eq!(a, b);
+
+        b.push_back(2);
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn head() {
+        let mut list = DlList::new();
+
+        assert!(list.head().is_none());
+
+        let one = list.push_back(1);
+
+        assert_eq!(list.head().unwrap(), &1);
+
+        list.push_back(2);
+
+        list.remove(one);
+
+        assert_eq!(list.head().unwrap(), &2);
+        assert_eq!(list.data[0], DlEntry::Free { next_free: None });
+        assert_eq!(list.head, 1);
+        assert_eq!(
+            list.data[1],
+            DlEntry::Occupied(OcEntry {
+                item: 2,
+                next: SENTINEL,
+                prev: SENTINEL,
+                rm_cnt: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn head_index() {
+        let mut list = DlList::new();
+
+        assert!(list.head_index().is_none());
+
+        let one = list.push_back(1);
+
+        assert_eq!(list.head_index().unwrap(), one);
+    }
+
+    #[test]
+    fn tail_index() {
+        let mut list = DlList::new();
+
+        assert!(list.tail_index().is_none());
+
+        list.push_back(1);
+        let two = list.push_back(2);
+
+        assert_eq!(list.tail_index().unwrap(), two);
+    }
+
+    #[test]
+    fn push_back() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+
+        assert_eq!(
+            list.data[0],
+            DlEntry::Occupied(OcEntry {
+                item: 1,
+                next: SENTINEL,
+                prev: SENTINEL,
+                rm_cnt: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn push_front() {
+        let mut list = DlList::new();
+
+        list.push_front(1);
+        list.push_front(2);
+
+        assert_eq!(
+            list.data[0],
+            DlEntry::Occupied(OcEntry {
+                item: 1,
+                next: SENTINEL,
+                prev: 1,
+                rm_cnt: 0,
+            })
+        );
+        assert_eq!(
+            list.data[1],
+            DlEntry::Occupied(OcEntry {
+                item: 2,
+                next: 0,
+                prev: SENTINEL,
+                rm_cnt: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn contains() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+
+        assert!(list.contains(&1));
+    }
+
+    #[test]
+    fn get() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let entry = list.get(one);
+
+        assert!(entry.is_some());
+        assert_eq!(entry.unwrap(), &1);
+    }
+
+    #[test]
+    fn next_index() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        list.push_back(2);
+
+        let two_index = list.next_index(one).unwrap();
+        let two_value = list.get(two_index);
+
+        assert_eq!(two_value.unwrap(), &2);
+        assert_eq!(None, list.next_index(two_index));
+    }
+
+    #[test]
+    fn prev_index() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+        let two = list.push_back(2);
+
+        let one_index = list.prev_index(two).unwrap();
+        let one_value = list.get(one_index);
+
+        assert_eq!(one_value.unwrap(), &1);
+        assert_eq!(None, list.prev_index(one_index));
+    }
+
+    #[test]
+    fn index() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let entry = list[one];
+
+        assert_eq!(entry, 1);
+    }
+
+    #[test]
+    fn remove() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+        let four = list.push_back(4);
+        let five = list.push_back(5);
+
+        let removed_middle = list.remove(three).unwrap();
+        assert_eq!(removed_middle, 3);
+        assert_eq!(
+            list,
+            DlList {
+                data: vec![
+                    DlEntry::Occupied(OcEntry {
+                        item: 1,
+                        next: 1,
+                        prev: SENTINEL,
+                        rm_cnt: 0,
+                    }),
+                    DlEntry::Occupied(OcEntry {
+                        item: 2,
+                        next: 3,
+                        prev: 0,
+                        rm_cnt: 0,
+                    }),
+                    DlEntry::Free { next_free: None },
+                    DlEntry::Occupied(OcEntry {
+                        item: 4,
+                        next: 4,
+                        prev: 1,
+                        rm_cnt: 0,
+                    }),
+                    DlEntry::Occupied(OcEntry {
+                        item: 5,
+                        next: SENTINEL,
+                        prev: 3,
+                        rm_cnt: 0,
+                    }),
+                ],
+                next_free: Some(2),
+                head: 0,
+                tail: 4,
+                rm_cnt: 1,
+                len: 4,
+                max_slots: usize::MAX,
+                reuse: ReusePolicy::Lifo,
+                trim_threshold: usize::MAX,
+                last_free: None,
+                freed_at: Vec::new(),
+                list_id: Provenance(ListId::new()),
+                origins: Provenance(Vec::new()),
+            }
+        );
+
+        let removed_head = list.remove(one).unwrap();
+        assert_eq!(removed_head, 1);
+        assert_eq!(
+            list,
+            DlList {
+                data: vec![
+                    DlEntry::Free { next_free: Some(2) },
+                    DlEntry::Occupied(OcEntry {
+                        item: 2,
+                        next: 3,
+                        prev: SENTINEL,
+                        rm_cnt: 0,
+                    }),
+                    DlEntry::Free { next_free: None },
+                    DlEntry::Occupied(OcEntry {
+                        item: 4,
+                        next: 4,
+                        prev: 1,
+                        rm_cnt: 0,
+                    }),
+                    DlEntry::Occupied(OcEntry {
+                        item: 5,
+                        next: SENTINEL,
+                        prev: 3,
+                        rm_cnt: 0,
+                    }),
+                ],
+                next_free: Some(0),
+                head: 1,
+                tail: 4,
+                rm_cnt: 2,
+                len: 3,
+                max_slots: usize::MAX,
+                reuse: ReusePolicy::Lifo,
+                trim_threshold: usize::MAX,
+                last_free: None,
+                freed_at: Vec::new(),
+                list_id: Provenance(ListId::new()),
+                origins: Provenance(Vec::new()),
+            }
+        );
+
+        let removed_tail = list.remove(five).unwrap();
+        assert_eq!(removed_tail, 5);
+        assert_eq!(
+            list,
+            DlList {
+                data: vec![
+                    DlEntry::Free { next_free: Some(2) },
+                    DlEntry::Occupied(OcEntry {
+                        item: 2,
+                        next: 3,
+                        prev: SENTINEL,
+                        rm_cnt: 0,
+                    }),
+                    DlEntry::Free { next_free: None },
+                    DlEntry::Occupied(OcEntry {
+                        item: 4,
+                        next: SENTINEL,
+                        prev: 1,
+                        rm_cnt: 0,
+                    }),
+                    DlEntry::Free { next_free: Some(0) },
+                ],
+                next_free: Some(4),
+                head: 1,
+                tail: 3,
+                rm_cnt: 3,
+                len: 2,
+                max_slots: usize::MAX,
+                reuse: ReusePolicy::Lifo,
+                trim_threshold: usize::MAX,
+                last_free: None,
+                freed_at: Vec::new(),
+                list_id: Provenance(ListId::new()),
+                origins: Provenance(Vec::new()),
+            }
+        );
+
+        let removed_two = list.remove(two).unwrap();
+        let removed_four = list.remove(four).unwrap();
+        assert_eq!(removed_two,2);
+        assert_eq!(removed_four, 4);
+
+        assert_eq!(
+            list,
+            DlList {
+                data: vec![
+                    DlEntry::Free { next_free: Some(2) },
+                    DlEntry::Free { next_free: Some(4) },
+                    DlEntry::Free { next_free: None },
+                    DlEntry::Free { next_free: Some(1) },
+                    DlEntry::Free { next_free: Some(0) },
+                ],
+                next_free: Some(3),
+                head: SENTINEL,
+                tail: SENTINEL,
+                rm_cnt: 5,
+                len: 0,
+                max_slots: usize::MAX,
+                reuse: ReusePolicy::Lifo,
+                trim_threshold: usize::MAX,
+                last_free: None,
+                freed_at: Vec::new(),
+                list_id: Provenance(ListId::new()),
+                origins: Provenance(Vec::new()),
+            }
+        );
+
+        assert!(list.remove(five).is_none());
+    }
+
+    #[test]
+    fn push_after_remove() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+        let two = list.push_back(2);
+        list.push_back(3);
+
+        let two = list.remove(two).unwrap();
+
+        assert_eq!(two, 2);
+
+        list.push_back(4);
+
+        assert_eq!(
+            list.data[0],
+            DlEntry::Occupied(OcEntry {
+                item: 1,
+                next: 2,
+                prev: SENTINEL,
+                rm_cnt: 0,
+            })
+        );
+
+        assert_eq!(
+            list.data[1],
+            DlEntry::Occupied(OcEntry {
+                item: 4,
+                next: SENTINEL,
+                prev: 2,
+                rm_cnt: 1,
+            })
+        );
+
+        assert_eq!(
+            list.data[2],
+            DlEntry::Occupied(OcEntry {
+                item: 3,
+                next: 1,
+                prev: 0,
+                rm_cnt: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn iter() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+        let two = list.push_back(2);
+        list.push_back(3);
+
+        list.remove(two);
+
+        let mut iter = list.iter();
+
+        assert_eq!(iter.next().unwrap(), &1);
+        assert_eq!(iter.next().unwrap(), &3);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn index_of() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(1);
+
+        assert_eq!(list.index_of(&1).unwrap(), DlIndex::new(0, 0, list.list_id.0));
+        assert!(list.index_of(&3).is_none());
+    }
+
+    #[test]
+    fn pop_front() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        assert_eq!(list.pop_front().unwrap(), 1);
+        assert_eq!(list.pop_front().unwrap(), 2);
+        assert_eq!(list.pop_front().unwrap(), 3);
+
+        assert_eq!(
+            list,
+            DlList {
+                data: vec![
+                    DlEntry::Free { next_free: None },
+                    DlEntry::Free { next_free: Some(0) },
+                    DlEntry::Free { next_free: Some(1) },
+                ],
+                next_free: Some(2),
+                head: SENTINEL,
+                tail: SENTINEL,
+                rm_cnt: 3,
+                len: 0,
+                max_slots: usize::MAX,
+                reuse: ReusePolicy::Lifo,
+                trim_threshold: usize::MAX,
+                last_free: None,
+                freed_at: Vec::new(),
+                list_id: Provenance(ListId::new()),
+                origins: Provenance(Vec::new()),
+            }
+        );
+    }
+
+    #[test]
+    fn len() {
+        let mut list = DlList::new();
+
+        assert!(list.is_empty());
+
+        let one = list.push_back(1);
+        list.push_front(2);
+        list.push_back(3);
+
+        assert_eq!(list.len(), 3);
+
+        list.remove(one);
+        list.pop_front();
+
+        assert_eq!(list.len(), 1);
+        assert!(!list.is_empty());
+    }
+
+    #[test]
+    fn nth() {
+        let mut list = DlList::new();
+
+        for i in 0..5 {
+            list.push_back(i);
+        }
+
+        assert_eq!(list.nth(0).unwrap(), &0);
+        assert_eq!(list.nth(1).unwrap(), &1);
+        assert_eq!(list.nth(3).unwrap(), &3);
+        assert_eq!(list.nth(4).unwrap(), &4);
+        assert!(list.nth(5).is_none());
+    }
+
+    #[test]
+    fn nth_index() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        assert_eq!(list.nth_index(0).unwrap(), one);
+        assert_eq!(list.nth_index(1).unwrap(), two);
+        assert_eq!(list.nth_index(2).unwrap(), three);
+        assert!(list.nth_index(3).is_none());
+    }
+
+    #[test]
+    fn position_of() {
+        let mut list = DlList::new();
+
+        let indexes: Vec<_> = (0..6).map(|i| list.push_back(i)).collect();
+
+        for (pos, index) in indexes.iter().enumerate() {
+            assert_eq!(list.position_of(*index), Some(pos));
+        }
+
+        list.remove(indexes[0]);
+
+        assert_eq!(list.position_of(indexes[0]), None);
+        assert_eq!(list.position_of(indexes[1]), Some(0));
+        assert_eq!(list.position_of(indexes[5]), Some(4));
+    }
+
+    #[test]
+    fn insert_at() {
+        let mut list = DlList::new();
+
+        list.insert_at(0, 2);
+        list.insert_at(0, 0);
+        list.insert_at(1, 1);
+        list.insert_at(3, 4);
+        let three = list.insert_at(3, 3);
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(list.position_of(three), Some(3));
+        assert_eq!(list.len(), 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_at_out_of_bounds() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+        list.insert_at(2, 2);
+    }
+
+    #[test]
+    fn remove_at() {
+        let mut list = DlList::new();
+
+        for i in 0..5 {
+            list.push_back(i);
+        }
+
+        assert_eq!(list.remove_at(3), Some(3));
+        assert_eq!(list.remove_at(0), Some(0));
+        assert_eq!(list.remove_at(3), None);
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 4]);
+    }
+
+    // Checks that every `next` link has a matching `prev` link and that
+    // `head`/`tail` are the ends of the chain, returning the items in order.
+    fn check_links<T: Copy + PartialEq + std::fmt::Debug>(list: &DlList<T>) -> Vec<T> {
+        let mut items = Vec::new();
+        let mut prev = SENTINEL;
+        let mut next = list.head;
+
+        while next != SENTINEL {
+            let entry = list.occupied(next);
+
+            assert_eq!(entry.prev, prev);
+            items.push(entry.item);
+            prev = next;
+            next = entry.next;
+        }
+
+        assert_eq!(list.tail, prev);
+        assert_eq!(items.len(), list.len());
+
+        items
+    }
+
+    #[test]
+    fn reverse() {
+        let mut list = DlList::new();
+
+        list.reverse();
+        assert!(list.is_empty());
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        list.reverse();
+
+        assert_eq!(check_links(&list), vec![3, 2, 1]);
+        assert_eq!(list.head_index(), Some(three));
+        assert_eq!(list.tail_index(), Some(one));
+        assert_eq!(list[two], 2);
+    }
 
-                Some(&e.item)
-            }
+    #[test]
+    fn rotate_left() {
+        let mut list = DlList::new();
+
+        for i in 0..5 {
+            list.push_back(i);
         }
+
+        list.rotate_left(2);
+        assert_eq!(check_links(&list), vec![2, 3, 4, 0, 1]);
+
+        list.rotate_left(5);
+        assert_eq!(check_links(&list), vec![2, 3, 4, 0, 1]);
+
+        list.rotate_left(8);
+        assert_eq!(check_links(&list), vec![0, 1, 2, 3, 4]);
     }
-}
 
-impl<T> std::ops::Index<DlIndex<T>> for DlList<T>
-where
-    T: PartialEq,
-    T: std::fmt::Debug,
-{
-    type Output = T;
+    #[test]
+    fn rotate_right() {
+        let mut list = DlList::new();
 
-    fn index(&self, index: DlIndex<T>) -> &Self::Output {
-        self.get(index).unwrap()
+        list.rotate_right(3);
+
+        for i in 0..5 {
+            list.push_back(i);
+        }
+
+        list.rotate_right(1);
+        assert_eq!(check_links(&list), vec![4, 0, 1, 2, 3]);
+
+        list.rotate_right(0);
+        assert_eq!(check_links(&list), vec![4, 0, 1, 2, 3]);
     }
-}
 
-// Start Tests
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn rotate_to_front() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        list.push_back(2);
+        let three = list.push_back(3);
+
+        assert!(list.rotate_to_front(three).is_ok());
+        assert_eq!(check_links(&list), vec![3, 1, 2]);
+        assert_eq!(list.head_index(), Some(three));
+
+        assert!(list.rotate_to_front(one).is_ok());
+        assert_eq!(check_links(&list), vec![1, 2, 3]);
+
+        list.remove(three);
+        assert_eq!(list.rotate_to_front(three), Err(DlError::StaleIndex));
+    }
 
     #[test]
-    fn create_list() {
-        let _list: DlList<i32> = DlList::new();
+    fn swap() {
+        let mut list = DlList::new();
+
+        let indexes: Vec<_> = (0..5).map(|i| list.push_back(i)).collect();
+
+        assert!(list.swap(indexes[0], indexes[4]).is_ok());
+        assert_eq!(check_links(&list), vec![4, 1, 2, 3, 0]);
+        assert_eq!(list.head_index(), Some(indexes[4]));
+        assert_eq!(list.tail_index(), Some(indexes[0]));
+
+        assert!(list.swap(indexes[1], indexes[2]).is_ok());
+        assert_eq!(check_links(&list), vec![4, 2, 1, 3, 0]);
+
+        assert!(list.swap(indexes[3], indexes[1]).is_ok());
+        assert_eq!(check_links(&list), vec![4, 2, 3, 1, 0]);
+
+        assert!(list.swap(indexes[2], indexes[2]).is_ok());
+        assert_eq!(check_links(&list), vec![4, 2, 3, 1, 0]);
+
+        list.remove(indexes[2]);
+        assert_eq!(list.swap(indexes[2], indexes[0]), Err(DlError::StaleIndex));
+        assert_eq!(list[indexes[0]], 0);
     }
 
     #[test]
-    fn head() {
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    fn foreign_index() {
+        let mut a = DlList::new();
+        let mut b = DlList::new();
+
+        let one = a.push_back(1);
+        b.push_back(1);
+
+        assert!(b.get(one).is_none());
+        assert_eq!(b.try_get(one), Err(DlError::ForeignIndex));
+        assert_eq!(b.try_remove(one), Err(DlError::ForeignIndex));
+        assert_eq!(b.len(), 1);
+
+        assert_eq!(a.try_remove(one), Ok(1));
+        assert_eq!(a.try_get(one), Err(DlError::StaleIndex));
+    }
+
+    #[test]
+    fn index_status() {
         let mut list = DlList::new();
 
-        assert!(list.head().is_none());
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+
+        assert_eq!(list.index_status(one), IndexStatus::Live);
+        assert!(list.contains_index(one));
+        assert!(list.is_valid(one));
+
+        list.remove(two);
+        assert_eq!(list.index_status(two), IndexStatus::Removed);
+        assert!(!list.contains_index(two));
+
+        list.push_back(3);
+        assert_eq!(
+            list.index_status(two),
+            IndexStatus::Reused { current_gen: 1 }
+        );
+        assert!(!list.is_valid(two));
+
+        let forged = DlIndex::new(5, 0, list.list_id.0);
+        assert_eq!(list.index_status(forged), IndexStatus::OutOfBounds);
+    }
+
+    #[test]
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    fn index_status_foreign() {
+        let mut a = DlList::new();
+        let b: DlList<i32> = DlList::new();
+
+        let one = a.push_back(1);
+
+        assert_eq!(b.index_status(one), IndexStatus::Foreign);
+        assert!(!b.is_valid(one));
+    }
+
+    #[test]
+    fn insert_after() {
+        let mut list = DlList::new();
 
         let one = list.push_back(1);
+        let three = list.insert_after(one, 3).unwrap();
+        list.insert_after(one, 2).unwrap();
 
-        assert_eq!(list.head().unwrap(), &1);
+        assert_eq!(list.tail_index(), Some(three));
+        assert_eq!(check_links(&list), vec![1, 2, 3]);
+
+        list.remove(one);
+        assert_eq!(list.insert_after(one, 4), Err(DlError::StaleIndex));
+    }
+
+    #[test]
+    fn push_with() {
+        #[derive(Debug, PartialEq)]
+        struct Node {
+            value: i32,
+            index: DlIndex<Node>,
+        }
+
+        let mut list = DlList::new();
+
+        let one = list.push_back_with(|index| Node { value: 1, index });
+        let zero = list.push_front_with(|index| Node { value: 0, index });
+        let two = list
+            .insert_after_with(one, |index| Node { value: 2, index })
+            .unwrap();
+
+        assert_eq!(list[zero].index, zero);
+        assert_eq!(list[one].index, one);
+        assert_eq!(list[two].index, two);
+        assert_eq!(list.iter().map(|n| n.value).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        list.remove(zero);
+
+        let three = list.push_back_with(|index| Node { value: 3, index });
+        assert_eq!(list[three].index, three);
+        assert_eq!(three.index, zero.index);
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        *list.get_mut(one).unwrap() += 10;
+
+        assert_eq!(list[one], 11);
+
+        list.remove(one);
+        assert!(list.get_mut(one).is_none());
+    }
+
+    #[test]
+    fn move_to_front() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        assert!(list.move_to_front(two).is_ok());
+        assert_eq!(check_links(&list), vec![2, 1, 3]);
+
+        assert!(list.move_to_front(three).is_ok());
+        assert_eq!(check_links(&list), vec![3, 2, 1]);
+
+        assert!(list.move_to_front(three).is_ok());
+        assert_eq!(check_links(&list), vec![3, 2, 1]);
+
+        assert!(list.move_to_back(three).is_ok());
+        assert_eq!(check_links(&list), vec![2, 1, 3]);
+        assert_eq!(list.tail_index(), Some(three));
+
+        list.remove(one);
+        assert_eq!(list.move_to_front(one), Err(DlError::StaleIndex));
+    }
+
+    #[test]
+    fn move_single() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+
+        assert!(list.move_to_front(one).is_ok());
+        assert!(list.move_to_back(one).is_ok());
+        assert_eq!(check_links(&list), vec![1]);
+    }
+
+    #[test]
+    fn replace() {
+        let mut list = DlList::new();
 
+        let one = list.push_back(1);
         list.push_back(2);
 
+        assert_eq!(list.replace(one, 10), Ok(1));
+        assert_eq!(list[one], 10);
+        assert_eq!(list.head_index(), Some(one));
+        assert_eq!(list.position_of(one), Some(0));
+
         list.remove(one);
+        assert_eq!(list.replace(one, 100), Err(DlError::StaleIndex));
+    }
 
-        assert_eq!(list.head().unwrap(), &2);
-        assert_eq!(list.data[0], DlEntry::Free { next_free: None });
-        assert_eq!(list.head, Some(1));
-        assert_eq!(
-            list.data[1],
-            DlEntry::Occupied(OcEntry {
-                item: 2,
-                next: None,
-                prev: None,
-                rm_cnt: 0,
-            })
-        );
+    #[test]
+    fn swap_values() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        assert!(list.swap_values(three, one).is_ok());
+        assert_eq!(check_links(&list), vec![3, 2, 1]);
+        assert_eq!(list[one], 3);
+        assert_eq!(list.head_index(), Some(one));
+
+        assert!(list.swap_values(two, two).is_ok());
+        assert_eq!(check_links(&list), vec![3, 2, 1]);
+
+        list.remove(two);
+        assert_eq!(list.swap_values(one, two), Err(DlError::StaleIndex));
     }
 
     #[test]
-    fn head_index() {
+    fn update() {
         let mut list = DlList::new();
 
-        assert!(list.head_index().is_none());
+        let one = list.push_back(1);
+
+        assert_eq!(list.update(one, |item| *item += 1), Ok(()));
+        assert_eq!(list.update(one, |item| *item * 10), Ok(20));
+        assert_eq!(list[one], 2);
+
+        list.remove(one);
+        assert_eq!(list.update(one, |item| *item), Err(DlError::StaleIndex));
+    }
+
+    #[test]
+    fn map() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+        list.remove(two);
+
+        let labels = list.map_ref(|i| i.to_string());
+
+        assert_eq!(labels[one.cast()], "1");
+        assert_eq!(labels.get(two.cast()), None);
+        assert_eq!(labels.next_free, list.next_free);
+        assert_eq!(labels.rm_cnt, list.rm_cnt);
+
+        let doubled = list.map(|i| i * 2);
+
+        assert_eq!(doubled[one], 2);
+        assert_eq!(doubled[three], 6);
+        assert_eq!(doubled.iter().copied().collect::<Vec<_>>(), vec![2, 6]);
+
+        let mut shifted = doubled.map(|i| i + 2);
+
+        // Still the original layout, so the original handles carry over.
+        assert_eq!(shifted[one.cast()], 4);
+        assert_eq!(shifted.try_get(three.cast()), Ok(&8));
+
+        let four = shifted.push_back(4);
+        assert_eq!(four.index, two.index);
+    }
+
+    #[test]
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    fn map_cast_foreign() {
+        let mut list = DlList::new();
+        let mut other = DlList::new();
+
+        let one = list.push_back(1);
+        other.push_back(1);
+
+        let labels = other.map_ref(|i| i.to_string());
+
+        assert_eq!(labels.try_get(one.cast()), Err(DlError::ForeignIndex));
+    }
+
+    #[test]
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    fn map_diverged() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        list.remove(two);
+
+        let mut labels = list.map_ref(|i| i.to_string());
+
+        // Both lists now fill the freed slot under the same generation.
+        let three = list.push_back(3);
+        let label = labels.push_back("three".to_string());
+
+        assert_eq!(three.index, label.index);
+        assert_eq!(list.try_get(label.cast()), Err(DlError::ForeignIndex));
+        assert_eq!(labels.try_get(three.cast()), Err(DlError::ForeignIndex));
+        assert_eq!(labels.try_get(one.cast()), Err(DlError::ForeignIndex));
+        assert_eq!(labels[label], "three");
+    }
+
+    #[test]
+    fn index_hash() {
+        use std::collections::HashSet;
+
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        list.remove(one);
+        let two = list.push_back(2);
+
+        let set: HashSet<_> = vec![one, two, two].into_iter().collect();
 
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&one));
+    }
+
+    #[test]
+    fn raw_index() {
+        let mut list = DlList::new();
+
+        list.push_back(0);
+        let one = list.push_back(1);
+        list.remove(one);
+        let two = list.push_back(2);
+
+        assert_eq!(two.to_raw(), (1 << 32) | 1);
+        assert_eq!(list.index_from_raw(two.to_raw()), Ok(two));
+        assert_eq!(list.index_from_raw(one.to_raw()), Err(DlError::StaleIndex));
+        assert_eq!(list.index_from_raw(u64::MAX), Err(DlError::StaleIndex));
+    }
+
+    #[test]
+    fn raw_index_wraps_generation() {
+        let mut list = DlList::new();
+
+        list.rm_cnt = u32::MAX as usize + 3;
+        let one = list.push_back(1);
+
+        assert_eq!(one.to_raw() >> 32, 2);
+        assert_eq!(list.index_from_raw(one.to_raw()), Ok(one));
+    }
+
+    #[test]
+    fn index_from_str() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        list.remove(one);
+
+        assert_eq!(two.to_string(), "1v0");
+        assert_eq!(list.index_from_str("1v0"), Ok(two));
+        assert_eq!(list.index_from_str(&one.to_string()), Err(DlError::StaleIndex));
+        assert_eq!(list.index_from_str("7v0"), Err(DlError::StaleIndex));
+        assert_eq!(list.index_from_str("1"), Err(DlError::Malformed));
+        assert_eq!(list.index_from_str("av0"), Err(DlError::Malformed));
+    }
+
+    #[test]
+    fn reserve() {
+        let mut list = DlList::with_capacity(2);
+
+        assert!(list.capacity() >= 2);
+
+        let zero = list.push_back(0);
+        list.push_back(1);
+        list.remove(zero);
+
+        // One free slot already counts towards the reservation.
+        list.reserve(3);
+        assert!(list.capacity() >= 4);
+
+        assert!(list.try_reserve(usize::MAX).is_err());
+        assert!(list.try_reserve(10).is_ok());
+        assert!(list.capacity() >= 11);
+    }
+
+    #[test]
+    fn max_slots() {
+        let mut list = DlList::with_max_slots(2);
+
+        assert_eq!(list.max_slots(), Some(2));
+        assert_eq!(DlList::<u32>::new().max_slots(), None);
+
+        let zero = list.try_push_back(0).unwrap();
+        list.try_push_front(1).unwrap();
+
+        assert_eq!(list.try_push_back(2), Err(2));
+        assert_eq!(list.try_push_front(3), Err(3));
+
+        list.reserve(5);
+        assert_eq!(list.data.len(), 2);
+
+        list.remove(zero);
+        let four = list.try_push_back(4).unwrap();
+
+        assert_eq!(four.index, zero.index);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "slot limit (is 1) reached")]
+    fn max_slots_push() {
+        let mut list = DlList::with_max_slots(1);
+
+        list.push_back(0);
+        list.push_back(1);
+    }
+
+    // Frees slots 3, 1 and 4 of a five-item list, in that order, and returns
+    // the slots the next three pushes land in.
+    fn reused_slots(reuse: ReusePolicy) -> Vec<usize> {
+        let mut list = DlList::with_reuse_policy(reuse);
+        let indexes: Vec<_> = (0..5).map(|i| list.push_back(i)).collect();
+
+        for &i in &[3, 1, 4] {
+            list.remove(indexes[i]);
+        }
+
+        (0..3)
+            .map(|i| {
+                let slot = list.vacant_entry().index().index;
+                let index = list.push_back(i);
+
+                assert_eq!(index.index, slot);
+
+                slot
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reuse_policy() {
+        assert_eq!(reused_slots(ReusePolicy::Lifo), vec![4, 1, 3]);
+        assert_eq!(reused_slots(ReusePolicy::Fifo), vec![3, 1, 4]);
+        assert_eq!(reused_slots(ReusePolicy::LowestIndex), vec![1, 3, 4]);
+        assert_eq!(reused_slots(ReusePolicy::Quarantine(2)), vec![3, 1, 4]);
+        assert_eq!(reused_slots(ReusePolicy::Quarantine(3)), vec![5, 3, 1]);
+        assert_eq!(reused_slots(ReusePolicy::Quarantine(5)), vec![5, 6, 7]);
+    }
+
+    #[test]
+    fn quarantine() {
+        let mut list = DlList::with_reuse_policy(ReusePolicy::Quarantine(2));
+
+        let zero = list.push_back(0);
         let one = list.push_back(1);
+        list.remove(zero);
+
+        // Slot 0 was freed by the last operation, then one operation ago.
+        assert_eq!(list.push_back(2).index, 2);
+        assert_eq!(list.push_back(3).index, 3);
+        assert_eq!(list.push_back(4).index, 0);
+
+        // Removals count as well.
+        list.remove(one);
+        list.pop_front();
+        list.pop_front();
 
-        assert_eq!(list.head_index().unwrap(), one);
+        assert_eq!(list.push_back(5).index, 1);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(list.reuse_policy(), ReusePolicy::Quarantine(2));
     }
 
     #[test]
-    fn tail_index() {
-        let mut list = DlList::new();
-
-        assert!(list.tail_index().is_none());
+    fn quarantine_at_slot_cap() {
+        let mut list = DlList::with_reuse_policy(ReusePolicy::Quarantine(5));
+        list.set_max_slots(Some(2));
 
+        let zero = list.push_back(0);
         list.push_back(1);
-        let two = list.push_back(2);
+        list.remove(zero);
 
-        assert_eq!(list.tail_index().unwrap(), two);
+        assert_eq!(list.vacant_entry().index().index, 0);
+        assert_eq!(list.try_push_back(2).map(|i| i.index), Ok(0));
+        assert_eq!(list.try_push_back(3), Err(3));
     }
 
     #[test]
-    fn push_back() {
+    fn trim() {
         let mut list = DlList::new();
+        let indexes: Vec<_> = (0..6).map(|i| list.push_back(i)).collect();
 
-        list.push_back(1);
+        for &i in &[1, 4, 5, 3] {
+            list.remove(indexes[i]);
+        }
 
-        assert_eq!(
-            list.data[0],
-            DlEntry::Occupied(OcEntry {
-                item: 1,
-                next: None,
-                prev: None,
-                rm_cnt: 0,
-            })
-        );
+        assert_eq!(list.free_slots(), 4);
+        assert_eq!(list.trailing_free_slots(), 3);
+        assert_eq!(list.fragmentation(), 4.0 / 6.0);
+
+        assert_eq!(list.trim(), 3);
+        assert_eq!(list.trim(), 0);
+        assert_eq!(list.data.len(), 3);
+        assert_eq!(list.next_free, Some(1));
+        assert_eq!(list.index_status(indexes[4]), IndexStatus::OutOfBounds);
+        assert_eq!(check_links(&list), vec![0, 2]);
+        assert_eq!(list[indexes[2]], 2);
+
+        // The freed slot is reused first, then the storage grows again.
+        assert_eq!(list.push_back(6).index, 1);
+        let seven = list.push_back(7);
+
+        assert_eq!(seven.index, 3);
+        assert_ne!(seven, indexes[3]);
+        assert_eq!(list.get(indexes[3]), None);
+        assert_eq!(list.fragmentation(), 0.0);
     }
 
     #[test]
-    fn push_front() {
-        let mut list = DlList::new();
+    fn trim_fifo() {
+        let mut list = DlList::with_reuse_policy(ReusePolicy::Fifo);
+        let indexes: Vec<_> = (0..4).map(|i| list.push_back(i)).collect();
 
-        list.push_front(1);
-        list.push_front(2);
+        for &i in &[3, 0, 2] {
+            list.remove(indexes[i]);
+        }
 
-        assert_eq!(
-            list.data[0],
-            DlEntry::Occupied(OcEntry {
-                item: 1,
-                next: None,
-                prev: Some(1),
-                rm_cnt: 0,
-            })
-        );
-        assert_eq!(
-            list.data[1],
-            DlEntry::Occupied(OcEntry {
-                item: 2,
-                next: Some(0),
-                prev: None,
-                rm_cnt: 0,
-            })
-        );
+        assert_eq!(list.trim(), 2);
+        assert_eq!(list.last_free, Some(0));
+
+        list.remove(indexes[1]);
+        assert_eq!(list.push_back(4).index, 0);
+        assert_eq!(list.push_back(5).index, 1);
     }
 
     #[test]
-    fn contains() {
-        let mut list = DlList::new();
+    fn auto_trim() {
+        let mut list = DlList::with_trim_threshold(2);
+        let indexes: Vec<_> = (0..5).map(|i| list.push_back(i)).collect();
 
-        list.push_back(1);
+        assert_eq!(list.trim_threshold(), Some(2));
+        assert_eq!(DlList::<u32>::new().trim_threshold(), None);
 
-        assert!(list.contains(&1));
-    }
+        list.remove(indexes[4]);
+        assert_eq!(list.data.len(), 5);
 
-    #[test]
-    fn get() {
-        let mut list = DlList::new();
+        list.remove(indexes[3]);
+        assert_eq!(list.data.len(), 3);
 
-        let one = list.push_back(1);
-        let entry = list.get(one);
+        list.remove(indexes[1]);
+        list.remove(indexes[2]);
+        assert_eq!(list.data.len(), 1);
+        assert_eq!(list.next_free, None);
 
-        assert!(entry.is_some());
-        assert_eq!(entry.unwrap(), &1);
+        // A lone free slot is a run of one, short of the threshold.
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.data.len(), 1);
+        assert_eq!(list.push_back(5).index, 0);
     }
 
     #[test]
-    fn next_index() {
-        let mut list = DlList::new();
+    fn auto_trim_waits_for_half_the_free_slots() {
+        let mut list = DlList::with_trim_threshold(1);
+        let indexes: Vec<_> = (0..8).map(|i| list.push_back(i)).collect();
 
-        let one = list.push_back(1);
-        list.push_back(2);
+        for &i in &[0, 1, 2, 3] {
+            list.remove(indexes[i]);
+        }
 
-        let two_index = list.next_index(one).unwrap();
-        let two_value = list.get(two_index);
+        // One trailing slot against four free ones elsewhere.
+        list.remove(indexes[7]);
+        assert_eq!(list.data.len(), 8);
 
-        assert_eq!(two_value.unwrap(), &2);
-        assert_eq!(None, list.next_index(two_index));
+        list.remove(indexes[6]);
+        assert_eq!(list.data.len(), 8);
+
+        list.remove(indexes[5]);
+        assert_eq!(list.data.len(), 5);
+        assert_eq!(list.free_slots(), 4);
     }
 
     #[test]
-    fn prev_index() {
-        let mut list = DlList::new();
+    fn combined_settings() {
+        let mut list = DlList::with_reuse_policy(ReusePolicy::LowestIndex);
+        list.set_trim_threshold(Some(2));
+        list.set_max_slots(Some(4));
 
-        list.push_back(1);
-        let two = list.push_back(2);
+        let indexes: Vec<_> = (0..4).map(|i| list.push_back(i)).collect();
 
-        let one_index = list.prev_index(two).unwrap();
-        let one_value = list.get(one_index);
+        assert_eq!(list.try_push_back(4), Err(4));
 
-        assert_eq!(one_value.unwrap(), &1);
-        assert_eq!(None, list.prev_index(one_index));
+        for &i in &[3, 1, 2] {
+            list.remove(indexes[i]);
+        }
+
+        assert_eq!(list.data.len(), 1);
+        assert_eq!(list.push_back(5).index, 1);
+
+        // Lowering the cap below the slots in use keeps them.
+        list.set_max_slots(Some(1));
+        list.reserve(1);
+        assert!(list.try_reserve(1).is_ok());
+        assert_eq!(list.try_push_back(6), Err(6));
+        assert_eq!(list.len(), 2);
+
+        list.set_max_slots(None);
+        list.set_trim_threshold(None);
+        assert_eq!(list.max_slots(), None);
+        assert_eq!(list.trim_threshold(), None);
     }
 
     #[test]
-    fn index() {
+    fn set_reuse_policy() {
         let mut list = DlList::new();
+        let indexes: Vec<_> = (0..5).map(|i| list.push_back(i)).collect();
 
-        let one = list.push_back(1);
-        let entry = list[one];
+        for &i in &[1, 4, 3] {
+            list.remove(indexes[i]);
+        }
 
-        assert_eq!(entry, 1);
+        list.set_reuse_policy(ReusePolicy::LowestIndex);
+        assert_eq!(list.push_back(5).index, 1);
+        list.remove(indexes[0]);
+
+        list.set_reuse_policy(ReusePolicy::Fifo);
+        assert_eq!(list.push_back(6).index, 0);
+        assert_eq!(list.push_back(7).index, 3);
+
+        list.set_reuse_policy(ReusePolicy::Quarantine(2));
+        list.remove(indexes[2]);
+        assert_eq!(list.push_back(8).index, 5);
+        assert_eq!(list.push_back(9).index, 4);
+        assert_eq!(list.reuse_policy(), ReusePolicy::Quarantine(2));
+        assert_eq!(list.len(), 5);
     }
 
     #[test]
-    fn remove() {
-        let mut list = DlList::new();
-
+    fn branded_remove_does_not_trim() {
+        let mut list = DlList::with_trim_threshold(1);
+        let zero = list.push_back(0);
         let one = list.push_back(1);
-        let two = list.push_back(2);
-        let three = list.push_back(3);
-        let four = list.push_back(4);
-        let five = list.push_back(5);
 
-        let removed_middle = list.remove(three).unwrap();
-        assert_eq!(removed_middle, 3);
-        assert_eq!(
-            list,
-            DlList {
-                data: vec![
-                    DlEntry::Occupied(OcEntry {
-                        item: 1,
-                        next: Some(1),
-                        prev: None,
-                        rm_cnt: 0,
-                    }),
-                    DlEntry::Occupied(OcEntry {
-                        item: 2,
-                        next: Some(3),
-                        prev: Some(0),
-                        rm_cnt: 0,
-                    }),
-                    DlEntry::Free { next_free: None },
-                    DlEntry::Occupied(OcEntry {
-                        item: 4,
-                        next: Some(4),
-                        prev: Some(1),
-                        rm_cnt: 0,
-                    }),
-                    DlEntry::Occupied(OcEntry {
-                        item: 5,
-                        next: None,
-                        prev: Some(3),
-                        rm_cnt: 0,
-                    }),
-                ],
-                next_free: Some(2),
-                head: Some(0),
-                tail: Some(4),
-                rm_cnt: 1,
-            }
-        );
+        list.scope(|mut list| {
+            let one = list.brand(one).unwrap();
 
-        let removed_head = list.remove(one).unwrap();
-        assert_eq!(removed_head, 1);
-        assert_eq!(
-            list,
-            DlList {
-                data: vec![
-                    DlEntry::Free { next_free: Some(2) },
-                    DlEntry::Occupied(OcEntry {
-                        item: 2,
-                        next: Some(3),
-                        prev: None,
-                        rm_cnt: 0,
-                    }),
-                    DlEntry::Free { next_free: None },
-                    DlEntry::Occupied(OcEntry {
-                        item: 4,
-                        next: Some(4),
-                        prev: Some(1),
-                        rm_cnt: 0,
-                    }),
-                    DlEntry::Occupied(OcEntry {
-                        item: 5,
-                        next: None,
-                        prev: Some(3),
-                        rm_cnt: 0,
-                    }),
-                ],
-                next_free: Some(0),
-                head: Some(1),
-                tail: Some(4),
-                rm_cnt: 2,
-            }
-        );
+            assert_eq!(list.remove(one), Some(1));
+            assert_eq!(list.get(one), None);
+        });
 
-        let removed_tail = list.remove(five).unwrap();
-        assert_eq!(removed_tail, 5);
-        assert_eq!(
-            list,
-            DlList {
-                data: vec![
-                    DlEntry::Free { next_free: Some(2) },
-                    DlEntry::Occupied(OcEntry {
-                        item: 2,
-                        next: Some(3),
-                        prev: None,
-                        rm_cnt: 0,
-                    }),
-                    DlEntry::Free { next_free: None },
-                    DlEntry::Occupied(OcEntry {
-                        item: 4,
-                        next: None,
-                        prev: Some(1),
-                        rm_cnt: 0,
-                    }),
-                    DlEntry::Free { next_free: Some(0) },
-                ],
-                next_free: Some(4),
-                head: Some(1),
-                tail: Some(3),
-                rm_cnt: 3,
-            }
-        );
+        assert_eq!(list.data.len(), 2);
+        assert_eq!(list.remove(zero), Some(0));
+        assert!(list.data.is_empty());
+    }
 
-        let removed_two = list.remove(two).unwrap();
-        let removed_four = list.remove(four).unwrap();
-        assert_eq!(removed_two,2);
-        assert_eq!(removed_four, 4);
+    #[test]
+    fn stats() {
+        let mut list = DlList::with_capacity(8);
+        let indexes: Vec<_> = (0..4).map(|i| list.push_back(i)).collect();
+
+        list.remove(indexes[1]);
+        list.remove(indexes[3]);
+        list.push_front(4);
+        list.remove(indexes[0]);
+
+        let stats = list.stats();
 
         assert_eq!(
-            list,
-            DlList {
-                data: vec![
-                    DlEntry::Free { next_free: Some(2) },
-                    DlEntry::Free { next_free: Some(4) },
-                    DlEntry::Free { next_free: None },
-                    DlEntry::Free { next_free: Some(1) },
-                    DlEntry::Free { next_free: Some(0) },
-                ],
-                next_free: Some(3),
-                head: None,
-                tail: None,
-                rm_cnt: 5,
+            stats,
+            DlStats {
+                len: 2,
+                free_slots: 2,
+                free_chain_len: 2,
+                slots: 4,
+                capacity: list.capacity(),
+                bytes_per_node: core::mem::size_of::<DlEntry<OcEntry<i32>>>(),
+                generation: 3,
+                fragmentation: 0.5,
+                highest_occupied: Some(3),
+                total_inserts: 5,
+                total_removes: 3,
             }
         );
-
-        assert!(list.remove(five).is_none());
+        assert!(stats.capacity >= 8);
+        assert_eq!(DlList::<u8>::new().stats().highest_occupied, None);
     }
 
     #[test]
-    fn push_after_remove() {
-        let mut list = DlList::new();
-
-        list.push_back(1);
-        let two = list.push_back(2);
-        list.push_back(3);
+    fn clear() {
+        let mut list = DlList::with_capacity(8);
+        let zero = list.push_back(0);
+        let one = list.push_back(1);
+        list.remove(zero);
 
-        let two = list.remove(two).unwrap();
+        list.clear_keep_capacity();
 
-        assert_eq!(two, 2);
+        assert!(list.is_empty());
+        assert!(list.capacity() >= 8);
+        assert_eq!(check_links(&list), vec![]);
 
-        list.push_back(4);
+        // Slot 0 comes back, but not with a generation `one` could match.
+        let two = list.push_back(2);
 
-        assert_eq!(
-            list.data[0],
-            DlEntry::Occupied(OcEntry {
-                item: 1,
-                next: Some(2),
-                prev: None,
-                rm_cnt: 0,
-            })
-        );
+        assert_eq!(two.index, 0);
+        assert_eq!(list.get(one), None);
+        assert_eq!(list.stats().total_removes, 2);
 
-        assert_eq!(
-            list.data[1],
-            DlEntry::Occupied(OcEntry {
-                item: 4,
-                next: None,
-                prev: Some(2),
-                rm_cnt: 1,
-            })
-        );
+        list.clear();
 
-        assert_eq!(
-            list.data[2],
-            DlEntry::Occupied(OcEntry {
-                item: 3,
-                next: Some(1),
-                prev: Some(0),
-                rm_cnt: 0,
-            })
-        );
+        assert_eq!(list.capacity(), 0);
+        assert_eq!(list.get(two), None);
+        assert_eq!(list.head(), None);
     }
 
     #[test]
-    fn iter() {
+    fn truncate() {
         let mut list = DlList::new();
+        let indexes: Vec<_> = (0..6).map(|i| list.push_back(i)).collect();
 
-        list.push_back(1);
-        let two = list.push_back(2);
-        list.push_back(3);
+        list.truncate(4);
+        assert_eq!(check_links(&list), vec![0, 1, 2, 3]);
+        assert_eq!(list.get(indexes[4]), None);
 
-        list.remove(two);
+        list.truncate_front(2);
+        assert_eq!(check_links(&list), vec![2, 3]);
+        assert_eq!(list.get(indexes[1]), None);
 
-        let mut iter = list.iter();
+        list.truncate(5);
+        list.truncate_front(5);
+        assert_eq!(check_links(&list), vec![2, 3]);
 
-        assert_eq!(iter.next().unwrap(), &1);
-        assert_eq!(iter.next().unwrap(), &3);
-        assert!(iter.next().is_none());
+        list.truncate(0);
+        assert!(list.is_empty());
     }
 
     #[test]
-    fn index_of() {
-        let mut list = DlList::new();
+    fn truncate_trims() {
+        let mut list = DlList::with_trim_threshold(2);
+        (0..4).for_each(|i| {
+            list.push_back(i);
+        });
 
-        list.push_back(1);
-        list.push_back(2);
-        list.push_back(1);
+        list.truncate(1);
 
-        assert_eq!(list.index_of(&1).unwrap(), DlIndex::new(0, 0));
-        assert!(list.index_of(&3).is_none());
+        assert_eq!(list.data.len(), 1);
     }
 
     #[test]
-    fn pop_front() {
+    fn remove_range() {
         let mut list = DlList::new();
+        let indexes: Vec<_> = (0..6).map(|i| list.push_back(i)).collect();
 
-        list.push_back(1);
-        list.push_back(2);
-        list.push_back(3);
+        let removed: Vec<_> = list.remove_range(indexes[1], indexes[3]).unwrap().collect();
 
-        assert_eq!(list.pop_front().unwrap(), 1);
-        assert_eq!(list.pop_front().unwrap(), 2);
-        assert_eq!(list.pop_front().unwrap(), 3);
+        assert_eq!(removed, vec![1, 2, 3]);
+        assert_eq!(check_links(&list), vec![0, 4, 5]);
+        assert_eq!(list.get(indexes[2]), None);
 
-        assert_eq!(
-            list,
-            DlList {
-                data: vec![
-                    DlEntry::Free { next_free: None },
-                    DlEntry::Free { next_free: Some(0) },
-                    DlEntry::Free { next_free: Some(1) },
-                ],
-                next_free: Some(2),
-                head: None,
-                tail: None,
-                rm_cnt: 3,
-            }
-        );
+        // Dropping the iterator early still removes the rest of the range.
+        assert_eq!(list.remove_range(indexes[4], indexes[5]).unwrap().next(), Some(4));
+        assert_eq!(check_links(&list), vec![0]);
+
+        assert_eq!(list.remove_range(indexes[0], indexes[0]).unwrap().count(), 1);
+        assert!(list.is_empty());
+
+        assert!(list.remove_range(indexes[0], indexes[0]).is_err());
+    }
+
+    #[test]
+    fn remove_range_reversed() {
+        let mut list = DlList::new();
+        let zero = list.push_back(0);
+        let one = list.push_back(1);
+
+        assert!(matches!(
+            list.remove_range(one, zero),
+            Err(DlError::InvalidRange)
+        ));
+        assert_eq!(list.len(), 2);
     }
 }
@@ -0,0 +1,402 @@
+use super::{Chain, DlEntry, DlError, DlIndex, Iter, ListId, OcEntry, Provenance, SENTINEL};
+
+/// A `DlList` that keeps its `N` slots inline and never allocates.
+///
+/// Handles and free-list reuse behave exactly as in `DlList`; once all `N`
+/// slots are occupied, `push_*` hands the item back instead of growing.
+#[derive(Debug, PartialEq)]
+pub struct ArrayDlList<T, const N: usize> {
+    data: [DlEntry<OcEntry<T>>; N],
+    // Number of slots handed out so far; plays the part of `data.len()` in
+    // `DlList`.
+    used: usize,
+    next_free: Option<usize>,
+    head: usize,
+    tail: usize,
+    rm_cnt: usize,
+    len: usize,
+    list_id: Provenance,
+}
+
+impl<T, const N: usize> Default for ArrayDlList<T, N> {
+    fn default() -> Self {
+        ArrayDlList {
+            data: core::array::from_fn(|_| DlEntry::Free { next_free: None }),
+            used: Default::default(),
+            next_free: Default::default(),
+            head: SENTINEL,
+            tail: SENTINEL,
+            rm_cnt: Default::default(),
+            len: Default::default(),
+            list_id: Provenance(ListId::new()),
+        }
+    }
+}
+
+impl<T, const N: usize> ArrayDlList<T, N>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> ArrayDlList<T, N> {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[allow(dead_code)]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    #[allow(dead_code)]
+    pub fn head(&self) -> Option<&T> {
+        self.node(self.head).map(|e| &e.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn head_index(&self) -> Option<DlIndex<T>> {
+        self.handle(self.head)
+    }
+
+    #[allow(dead_code)]
+    pub fn tail_index(&self) -> Option<DlIndex<T>> {
+        self.handle(self.tail)
+    }
+
+    /// Appends `item`, or returns it if every slot is occupied.
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> Result<DlIndex<T>, T> {
+        let tail = self.tail;
+
+        self.link_between(tail, SENTINEL, item)
+    }
+
+    /// Prepends `item`, or returns it if every slot is occupied.
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> Result<DlIndex<T>, T> {
+        let head = self.head;
+
+        self.link_between(SENTINEL, head, item)
+    }
+
+    /// Inserts `item` right after `index`. The outer error is a bad handle;
+    /// the inner one hands the item back because the list is full.
+    #[allow(dead_code)]
+    pub fn insert_after(
+        &mut self,
+        index: DlIndex<T>,
+        item: T,
+    ) -> Result<Result<DlIndex<T>, T>, DlError> {
+        self.check(index)?;
+
+        let next = self.next_of(index.index);
+
+        Ok(self.link_between(index.index, next, item))
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, value: &T) -> bool {
+        self.iter().any(|e| e == value)
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex<T>) -> Option<&T> {
+        self.try_get(index).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn try_get(&self, index: DlIndex<T>) -> Result<&T, DlError> {
+        Ok(&self.check(index)?.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, index: DlIndex<T>) -> Option<&mut T> {
+        Some(&mut self.check_mut(index).ok()?.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn contains_index(&self, index: DlIndex<T>) -> bool {
+        self.check(index).is_ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn next_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        let next = self.check(index).ok()?.next;
+
+        self.handle(next)
+    }
+
+    #[allow(dead_code)]
+    pub fn prev_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        let prev = self.check(index).ok()?.prev;
+
+        self.handle(prev)
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex<T>) -> Option<T> {
+        self.try_remove(index).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn try_remove(&mut self, index: DlIndex<T>) -> Result<T, DlError> {
+        self.check(index)?;
+
+        Ok(self.remove_slot(index.index))
+    }
+
+    #[allow(dead_code)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head = self.head;
+
+        self.node(head)?;
+
+        Some(self.remove_slot(head))
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        Iter {
+            slots: &self.data,
+            next_index: self.head,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn nth(&self, pos: usize) -> Option<&T> {
+        let index = self.slot_at(pos)?;
+
+        Some(&self.occupied(index).item)
+    }
+
+    #[allow(dead_code)]
+    pub fn nth_index(&self, pos: usize) -> Option<DlIndex<T>> {
+        self.handle(self.slot_at(pos)?)
+    }
+
+    #[allow(dead_code)]
+    pub fn position_of(&self, index: DlIndex<T>) -> Option<usize> {
+        self.check(index).ok()?;
+
+        Some(self.slot_position(index.index))
+    }
+
+    #[allow(dead_code)]
+    pub fn reverse(&mut self) {
+        self.reverse_links();
+    }
+
+    #[allow(dead_code)]
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        if let Some(index) = self.slot_at(n % self.len) {
+            self.rotate_to_slot(index);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn swap(&mut self, a: DlIndex<T>, b: DlIndex<T>) -> Result<(), DlError> {
+        self.check(a)?;
+        self.check(b)?;
+
+        self.swap_slots(a.index, b.index);
+
+        Ok(())
+    }
+
+    fn link_between(&mut self, prev: usize, next: usize, item: T) -> Result<DlIndex<T>, T> {
+        let rm_cnt = self.rm_cnt;
+
+        let index = self.alloc(OcEntry {
+            item,
+            next,
+            prev,
+            rm_cnt,
+        })?;
+
+        self.relink(index);
+        self.len += 1;
+
+        Ok(DlIndex::new(index, rm_cnt, self.list_id.0))
+    }
+
+    fn remove_slot(&mut self, index: usize) -> T {
+        self.unlink(index);
+
+        let removed = core::mem::replace(
+            &mut self.data[index],
+            DlEntry::Free {
+                next_free: self.next_free,
+            },
+        );
+
+        self.next_free = Some(index);
+
+        self.rm_cnt += 1;
+        self.len -= 1;
+
+        match removed {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e.item,
+        }
+    }
+
+    fn alloc(&mut self, entry: OcEntry<T>) -> Result<usize, T> {
+        if let Some(position) = self.next_free {
+            match self.data[position] {
+                DlEntry::Occupied { .. } => panic!("list corrupted!"),
+                DlEntry::Free { next_free } => self.next_free = next_free,
+            }
+
+            self.data[position] = DlEntry::Occupied(entry);
+
+            Ok(position)
+        } else if self.used < N {
+            self.data[self.used] = DlEntry::Occupied(entry);
+            self.used += 1;
+
+            Ok(self.used - 1)
+        } else {
+            Err(entry.item)
+        }
+    }
+}
+
+impl<T, const N: usize> Chain<T> for ArrayDlList<T, N> {
+    type Node = OcEntry<T>;
+
+    fn slots(&self) -> &[DlEntry<OcEntry<T>>] {
+        &self.data
+    }
+
+    fn slots_mut(&mut self) -> &mut [DlEntry<OcEntry<T>>] {
+        &mut self.data
+    }
+
+    fn ends(&self) -> (usize, usize) {
+        (self.head, self.tail)
+    }
+
+    fn ends_mut(&mut self) -> (&mut usize, &mut usize) {
+        (&mut self.head, &mut self.tail)
+    }
+
+    fn count(&self) -> usize {
+        self.len
+    }
+
+    fn id(&self) -> ListId {
+        self.list_id.0
+    }
+}
+
+impl<T, const N: usize> core::ops::Index<DlIndex<T>> for ArrayDlList<T, N>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.try_get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_until_full() {
+        let mut list: ArrayDlList<u32, 3> = ArrayDlList::new();
+
+        let one = list.push_back(1).unwrap();
+        let zero = list.push_front(0).unwrap();
+        list.push_back(2).unwrap();
+
+        assert!(list.is_full());
+        assert_eq!(list.push_back(3), Err(3));
+        assert_eq!(list.push_front(4), Err(4));
+        assert_eq!(list.insert_after(one, 5), Ok(Err(5)));
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(list.head_index(), Some(zero));
+        assert_eq!(list[one], 1);
+    }
+
+    #[test]
+    fn eq_ignores_list_id() {
+        let mut a: ArrayDlList<u32, 2> = ArrayDlList::new();
+        let mut b: ArrayDlList<u32, 2> = ArrayDlList::new();
+
+        a.push_back(1).unwrap();
+        b.push_back(1).unwrap();
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn reuses_freed_slots() {
+        let mut list: ArrayDlList<u32, 2> = ArrayDlList::new();
+
+        let zero = list.push_back(0).unwrap();
+        list.push_back(1).unwrap();
+
+        assert_eq!(list.remove(zero), Some(0));
+        assert_eq!(list.get(zero), None);
+
+        let two = list.push_front(2).unwrap();
+
+        assert_eq!(two.index, zero.index);
+        assert_ne!(two, zero);
+        assert_eq!(list.push_back(3), Err(3));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn links() {
+        let mut list: ArrayDlList<u32, 4> = ArrayDlList::new();
+
+        let zero = list.push_back(0).unwrap();
+        let one = list.push_back(1).unwrap();
+        let three = list.push_back(3).unwrap();
+        let two = list.insert_after(one, 2).unwrap().unwrap();
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(list.nth(2), Some(&2));
+        assert_eq!(list.position_of(three), Some(3));
+        assert_eq!(list.next_index(one), Some(two));
+        assert_eq!(list.prev_index(zero), None);
+
+        list.reverse();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+
+        list.rotate_left(1);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 1, 0, 3]);
+
+        list.swap(zero, two).unwrap();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.tail_index(), Some(three));
+    }
+}
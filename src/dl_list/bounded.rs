@@ -0,0 +1,283 @@
+use alloc::boxed::Box;
+use core::fmt;
+
+use super::{DlIndex, DlList};
+
+// Picks the item to evict, given the list and the new item.
+type Hook<T> = Box<dyn FnMut(&DlList<T>, &T) -> Option<DlIndex<T>>>;
+
+/// What a full `BoundedDlList` does with a new item.
+pub enum OverflowPolicy<T> {
+    /// Hand the new item back.
+    Reject,
+    /// Drop the head to make room.
+    EvictFront,
+    /// Drop the tail to make room.
+    EvictBack,
+    /// Ask the hook which item to drop, given the list and the new item.
+    /// Returning `None`, or a handle that is not live, rejects the new item.
+    Hook(Hook<T>),
+}
+
+impl<T> fmt::Debug for OverflowPolicy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OverflowPolicy::Reject => write!(f, "Reject"),
+            OverflowPolicy::EvictFront => write!(f, "EvictFront"),
+            OverflowPolicy::EvictBack => write!(f, "EvictBack"),
+            OverflowPolicy::Hook(_) => write!(f, "Hook(..)"),
+        }
+    }
+}
+
+/// An item pushed out of a `BoundedDlList`, with the handle it had. The
+/// handle is already dead.
+#[derive(Debug, PartialEq)]
+pub struct Evicted<T> {
+    pub index: DlIndex<T>,
+    pub item: T,
+}
+
+/// A `DlList` that holds at most `capacity` items and applies an
+/// `OverflowPolicy` once it is full. Its slots grow on demand and never
+/// past `capacity`; use `with_reserved` to allocate them up front.
+#[derive(Debug)]
+pub struct BoundedDlList<T> {
+    list: DlList<T>,
+    capacity: usize,
+    policy: OverflowPolicy<T>,
+}
+
+impl<T> BoundedDlList<T>
+where
+    T: PartialEq,
+    T: fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn new(capacity: usize, policy: OverflowPolicy<T>) -> BoundedDlList<T> {
+        BoundedDlList {
+            list: DlList::with_max_slots(capacity),
+            capacity,
+            policy,
+        }
+    }
+
+    /// Like `new`, but allocates all `capacity` slots up front.
+    #[allow(dead_code)]
+    pub fn with_reserved(capacity: usize, policy: OverflowPolicy<T>) -> BoundedDlList<T> {
+        let mut list = Self::new(capacity, policy);
+
+        list.list.reserve(capacity);
+
+        list
+    }
+
+    #[allow(dead_code)]
+    pub fn list(&self) -> &DlList<T> {
+        &self.list
+    }
+
+    #[allow(dead_code)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_full(&self) -> bool {
+        self.list.len() >= self.capacity
+    }
+
+    #[allow(dead_code)]
+    pub fn set_policy(&mut self, policy: OverflowPolicy<T>) {
+        self.policy = policy;
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex<T>) -> Option<&T> {
+        self.list.get(index)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, index: DlIndex<T>) -> Option<&mut T> {
+        self.list.get_mut(index)
+    }
+
+    /// Appends `item`, evicting an item first if the list is full. Returns
+    /// the new handle and whatever was evicted, or hands `item` back if the
+    /// policy rejects it.
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> Result<(DlIndex<T>, Option<Evicted<T>>), T> {
+        let evicted = match self.make_room(&item) {
+            Some(evicted) => evicted,
+            None => return Err(item),
+        };
+
+        Ok((self.list.push_back(item), evicted))
+    }
+
+    /// Prepends `item`, evicting an item first if the list is full. Returns
+    /// the new handle and whatever was evicted, or hands `item` back if the
+    /// policy rejects it.
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> Result<(DlIndex<T>, Option<Evicted<T>>), T> {
+        let evicted = match self.make_room(&item) {
+            Some(evicted) => evicted,
+            None => return Err(item),
+        };
+
+        Ok((self.list.push_front(item), evicted))
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex<T>) -> Option<T> {
+        self.list.remove(index)
+    }
+
+    #[allow(dead_code)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.list.iter()
+    }
+
+    // Frees a slot for `item` if the list is full: `Some` with whatever was
+    // evicted, or `None` if the policy rejects `item`.
+    fn make_room(&mut self, item: &T) -> Option<Option<Evicted<T>>> {
+        if !self.is_full() {
+            return Some(None);
+        }
+
+        let index = match &mut self.policy {
+            OverflowPolicy::Reject => None,
+            OverflowPolicy::EvictFront => self.list.head_index(),
+            OverflowPolicy::EvictBack => self.list.tail_index(),
+            OverflowPolicy::Hook(hook) => hook(&self.list, item),
+        }?;
+
+        let item = self.list.remove(index)?;
+
+        Some(Some(Evicted { index, item }))
+    }
+}
+
+impl<T> core::ops::Index<DlIndex<T>> for BoundedDlList<T>
+where
+    T: PartialEq,
+    T: fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.list.try_get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reject() {
+        let mut list = BoundedDlList::with_reserved(2, OverflowPolicy::Reject);
+
+        assert!(list.list().capacity() >= 2);
+
+        let zero = list.push_back(0).unwrap().0;
+        list.push_front(1).unwrap();
+
+        assert!(list.is_full());
+        assert_eq!(list.push_back(2), Err(2));
+        assert_eq!(list.push_front(3), Err(3));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 0]);
+
+        list.remove(zero);
+        assert!(list.push_back(4).is_ok());
+        assert_eq!(list.list().data.len(), 2);
+    }
+
+    #[test]
+    fn evict_front() {
+        let mut list = BoundedDlList::new(2, OverflowPolicy::EvictFront);
+
+        let zero = list.push_back(0).unwrap().0;
+        list.push_back(1).unwrap();
+
+        let (two, evicted) = list.push_back(2).unwrap();
+
+        assert_eq!(
+            evicted,
+            Some(Evicted {
+                index: zero,
+                item: 0
+            })
+        );
+        assert_eq!(list.get(zero), None);
+        assert_eq!(list[two], 2);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn evict_back() {
+        let mut list = BoundedDlList::new(2, OverflowPolicy::EvictBack);
+
+        list.push_back(0).unwrap();
+        let one = list.push_back(1).unwrap().0;
+
+        let (_, evicted) = list.push_front(2).unwrap();
+
+        assert_eq!(evicted.map(|e| (e.index, e.item)), Some((one, 1)));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 0]);
+    }
+
+    #[test]
+    fn hook() {
+        // Evicts the smallest item, unless the new one is smaller still.
+        let policy = OverflowPolicy::Hook(Box::new(|list: &DlList<u32>, item: &u32| {
+            let min = list.iter().min()?;
+
+            if item < min {
+                return None;
+            }
+
+            list.index_of(min)
+        }));
+        let mut list = BoundedDlList::new(3, policy);
+
+        for i in [5, 2, 7] {
+            list.push_back(i).unwrap();
+        }
+
+        assert_eq!(list.push_back(1), Err(1));
+        assert_eq!(list.push_back(4).unwrap().1.map(|e| e.item), Some(2));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![5, 7, 4]);
+    }
+
+    #[test]
+    fn unbounded() {
+        let mut list = BoundedDlList::new(usize::MAX, OverflowPolicy::Reject);
+
+        assert!(list.push_back(0).is_ok());
+        assert!(!list.is_full());
+    }
+
+    #[test]
+    fn zero_capacity() {
+        let mut list = BoundedDlList::new(0, OverflowPolicy::EvictFront);
+
+        assert_eq!(list.push_back(0), Err(0));
+        assert!(list.is_empty());
+    }
+}
@@ -0,0 +1,255 @@
+use core::marker::PhantomData;
+
+use super::{Chain, DlEntry, DlIndex, DlList, OcEntry};
+
+// Invariant in `'id`, so two brands can never be unified by the compiler.
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/// A `DlList` borrowed for the duration of `DlList::scope`, whose handles are
+/// branded with a lifetime unique to that scope.
+///
+/// A `BrandedIndex<'id, T>` can only be produced by the `BrandedDlList<'id, T>`
+/// with the same brand, so passing a handle to the wrong list is a compile
+/// error rather than a runtime check.
+#[derive(Debug)]
+pub struct BrandedDlList<'id, 'a, T>
+where
+    T: 'a,
+{
+    list: &'a mut DlList<T>,
+    _brand: Brand<'id>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BrandedIndex<'id, T> {
+    index: usize,
+    rm_cnt: usize,
+    _brand: Brand<'id>,
+    _marker: PhantomData<T>,
+}
+
+impl<'id, T> Clone for BrandedIndex<'id, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'id, T> Copy for BrandedIndex<'id, T> {}
+
+impl<'id, T> BrandedIndex<'id, T> {
+    fn new(index: usize, rm_cnt: usize) -> BrandedIndex<'id, T> {
+        BrandedIndex {
+            index,
+            rm_cnt,
+            _brand: PhantomData,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> DlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    /// Runs `f` with a branded view of this list. Handles issued inside the
+    /// scope cannot be used with any other list, which lets lookups skip the
+    /// provenance and bounds checks that `DlIndex` needs.
+    ///
+    /// A handle from one scope is rejected by another list's scope:
+    ///
+    /// ```compile_fail
+    /// use dl_list::DlList;
+    ///
+    /// let mut a: DlList<u32> = DlList::new();
+    /// let mut b: DlList<u32> = DlList::new();
+    ///
+    /// a.scope(|mut a| {
+    ///     let one = a.push_back(1);
+    ///
+    ///     b.scope(|b| b.get(one).copied())
+    /// });
+    /// ```
+    ///
+    /// and a handle cannot outlive its scope:
+    ///
+    /// ```compile_fail
+    /// use dl_list::DlList;
+    ///
+    /// let mut list: DlList<u32> = DlList::new();
+    ///
+    /// let one = list.scope(|mut list| list.push_back(1));
+    /// ```
+    #[allow(dead_code)]
+    pub fn scope<'a, R, F>(&'a mut self, f: F) -> R
+    where
+        F: for<'id> FnOnce(BrandedDlList<'id, 'a, T>) -> R,
+    {
+        f(BrandedDlList {
+            list: self,
+            _brand: PhantomData,
+        })
+    }
+}
+
+impl<'id, 'a, T> BrandedDlList<'id, 'a, T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> BrandedIndex<'id, T> {
+        let index = self.list.push_back(item);
+
+        BrandedIndex::new(index.index, index.rm_cnt)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> BrandedIndex<'id, T> {
+        let index = self.list.push_front(item);
+
+        BrandedIndex::new(index.index, index.rm_cnt)
+    }
+
+    #[allow(dead_code)]
+    pub fn head_index(&self) -> Option<BrandedIndex<'id, T>> {
+        self.slot_index(self.list.head)
+    }
+
+    #[allow(dead_code)]
+    pub fn tail_index(&self) -> Option<BrandedIndex<'id, T>> {
+        self.slot_index(self.list.tail)
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: BrandedIndex<'id, T>) -> Option<&T> {
+        Some(&self.entry(index)?.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn next_index(&self, index: BrandedIndex<'id, T>) -> Option<BrandedIndex<'id, T>> {
+        let next = self.entry(index)?.next;
+
+        self.slot_index(next)
+    }
+
+    #[allow(dead_code)]
+    pub fn prev_index(&self, index: BrandedIndex<'id, T>) -> Option<BrandedIndex<'id, T>> {
+        let prev = self.entry(index)?.prev;
+
+        self.slot_index(prev)
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: BrandedIndex<'id, T>) -> Option<T> {
+        self.entry(index)?;
+
+        Some(self.list.remove_slot(index.index))
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.list.iter()
+    }
+
+    /// Brands a handle issued by the underlying list, validating it once.
+    #[allow(dead_code)]
+    pub fn brand(&self, index: DlIndex<T>) -> Option<BrandedIndex<'id, T>> {
+        self.list.check(index).ok()?;
+
+        Some(BrandedIndex::new(index.index, index.rm_cnt))
+    }
+
+    /// Converts a branded handle back into a `DlIndex` usable outside the
+    /// scope.
+    #[allow(dead_code)]
+    pub fn unbrand(&self, index: BrandedIndex<'id, T>) -> DlIndex<T> {
+        DlIndex::new(index.index, index.rm_cnt, self.list.list_id.0)
+    }
+
+    // A branded handle always names a slot of this list, and slots are never
+    // dropped from `data` while the list is borrowed (removals here skip the
+    // list's tail trimming), so only the generation needs checking.
+    fn entry(&self, index: BrandedIndex<'id, T>) -> Option<&OcEntry<T>> {
+        match &self.list.data[index.index] {
+            DlEntry::Occupied(e) if e.rm_cnt == index.rm_cnt => Some(e),
+            _ => None,
+        }
+    }
+
+    // The handle of the node at `index`, or `None` for the sentinel.
+    fn slot_index(&self, index: usize) -> Option<BrandedIndex<'id, T>> {
+        let entry = self.list.node(index)?;
+
+        Some(BrandedIndex::new(index, entry.rm_cnt))
+    }
+}
+
+impl<'id, 'a, T> core::ops::Index<BrandedIndex<'id, T>> for BrandedDlList<'id, 'a, T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: BrandedIndex<'id, T>) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope() {
+        let mut list = DlList::new();
+
+        let outside = list.push_back(0);
+
+        let (one, sum) = list.scope(|mut list| {
+            let one = list.push_back(1);
+            let two = list.push_back(2);
+
+            assert_eq!(list[one], 1);
+            assert_eq!(list.next_index(one), Some(two));
+            assert_eq!(list.prev_index(two), Some(one));
+            assert_eq!(list.tail_index(), Some(two));
+
+            assert_eq!(list.remove(two), Some(2));
+            assert_eq!(list.get(two), None);
+
+            (list.unbrand(one), list.iter().sum::<i32>())
+        });
+
+        assert_eq!(sum, 1);
+        assert_eq!(list[one], 1);
+        assert_eq!(list[outside], 0);
+    }
+
+    #[test]
+    fn brand() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        list.remove(two);
+
+        list.scope(|list| {
+            let one = list.brand(one).unwrap();
+
+            assert_eq!(list.head_index(), Some(one));
+            assert!(list.brand(two).is_none());
+        });
+    }
+}
@@ -0,0 +1,266 @@
+use super::{DlEntry, DlError, DlIndex, ListId, OcEntry, SENTINEL};
+
+/// The links and generation of a node, whether or not it also holds the
+/// item.
+pub(super) trait Node {
+    fn next(&self) -> usize;
+
+    fn prev(&self) -> usize;
+
+    fn next_mut(&mut self) -> &mut usize;
+
+    fn prev_mut(&mut self) -> &mut usize;
+
+    fn rm_cnt(&self) -> usize;
+}
+
+impl<T> Node for OcEntry<T> {
+    fn next(&self) -> usize {
+        self.next
+    }
+
+    fn prev(&self) -> usize {
+        self.prev
+    }
+
+    fn next_mut(&mut self) -> &mut usize {
+        &mut self.next
+    }
+
+    fn prev_mut(&mut self) -> &mut usize {
+        &mut self.prev
+    }
+
+    fn rm_cnt(&self) -> usize {
+        self.rm_cnt
+    }
+}
+
+/// The link manipulation shared by every list that keeps its nodes in a
+/// slice of `DlEntry<Node>` around a `SENTINEL` node.
+///
+/// Implementors only say where the slots, the sentinel's links, the length
+/// and the list id live; allocating and freeing slots stays with them.
+pub(super) trait Chain<T> {
+    type Node: Node;
+
+    fn slots(&self) -> &[DlEntry<Self::Node>];
+
+    fn slots_mut(&mut self) -> &mut [DlEntry<Self::Node>];
+
+    // The sentinel's `next` and `prev` links, i.e. the head and tail.
+    fn ends(&self) -> (usize, usize);
+
+    fn ends_mut(&mut self) -> (&mut usize, &mut usize);
+
+    fn count(&self) -> usize;
+
+    fn id(&self) -> ListId;
+
+    // Whether handles issued under `id` may be looked up here.
+    fn accepts(&self, id: ListId) -> bool {
+        id == self.id()
+    }
+
+    fn check(&self, index: DlIndex<T>) -> Result<&Self::Node, DlError> {
+        if !self.accepts(index.list_id) {
+            return Err(DlError::ForeignIndex);
+        }
+
+        match self.slots().get(index.index) {
+            Some(DlEntry::Occupied(e)) if e.rm_cnt() == index.rm_cnt => Ok(e),
+            _ => Err(DlError::StaleIndex),
+        }
+    }
+
+    fn check_mut(&mut self, index: DlIndex<T>) -> Result<&mut Self::Node, DlError> {
+        if !self.accepts(index.list_id) {
+            return Err(DlError::ForeignIndex);
+        }
+
+        match self.slots_mut().get_mut(index.index) {
+            Some(DlEntry::Occupied(e)) if e.rm_cnt() == index.rm_cnt => Ok(e),
+            _ => Err(DlError::StaleIndex),
+        }
+    }
+
+    // The node at `index`, or `None` for the sentinel.
+    fn node(&self, index: usize) -> Option<&Self::Node> {
+        match self.slots().get(index)? {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => Some(e),
+        }
+    }
+
+    // The handle of the node at `index`, or `None` for the sentinel.
+    fn handle(&self, index: usize) -> Option<DlIndex<T>> {
+        let entry = self.node(index)?;
+
+        Some(DlIndex::new(index, entry.rm_cnt(), self.id()))
+    }
+
+    fn occupied(&self, index: usize) -> &Self::Node {
+        match &self.slots()[index] {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e,
+        }
+    }
+
+    fn occupied_mut(&mut self, index: usize) -> &mut Self::Node {
+        match &mut self.slots_mut()[index] {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e,
+        }
+    }
+
+    fn next_of(&self, index: usize) -> usize {
+        match index {
+            SENTINEL => self.ends().0,
+            _ => self.occupied(index).next(),
+        }
+    }
+
+    fn prev_of(&self, index: usize) -> usize {
+        match index {
+            SENTINEL => self.ends().1,
+            _ => self.occupied(index).prev(),
+        }
+    }
+
+    fn next_mut<'a>(&'a mut self, index: usize) -> &'a mut usize
+    where
+        T: 'a,
+    {
+        match index {
+            SENTINEL => self.ends_mut().0,
+            _ => self.occupied_mut(index).next_mut(),
+        }
+    }
+
+    fn prev_mut<'a>(&'a mut self, index: usize) -> &'a mut usize
+    where
+        T: 'a,
+    {
+        match index {
+            SENTINEL => self.ends_mut().1,
+            _ => self.occupied_mut(index).prev_mut(),
+        }
+    }
+
+    // Points the neighbours named by `index`'s own links back at `index`.
+    fn relink(&mut self, index: usize) {
+        let (prev, next) = (self.prev_of(index), self.next_of(index));
+
+        *self.next_mut(prev) = index;
+        *self.prev_mut(next) = index;
+    }
+
+    // Takes `index` out of the chain, joining its neighbours. The node keeps
+    // its stale links until it is relinked.
+    fn unlink(&mut self, index: usize) {
+        let (prev, next) = (self.prev_of(index), self.next_of(index));
+
+        *self.next_mut(prev) = next;
+        *self.prev_mut(next) = prev;
+    }
+
+    // Points `index` at `prev` and `next` and points them back at `index`.
+    fn set_links(&mut self, index: usize, prev: usize, next: usize) {
+        *self.prev_mut(index) = prev;
+        *self.next_mut(index) = next;
+
+        self.relink(index);
+    }
+
+    fn slot_at(&self, pos: usize) -> Option<usize> {
+        let len = self.count();
+
+        if pos >= len {
+            return None;
+        }
+
+        let mut index = SENTINEL;
+
+        if pos < len - pos {
+            for _ in 0..=pos {
+                index = self.next_of(index);
+            }
+        } else {
+            for _ in pos..len {
+                index = self.prev_of(index);
+            }
+        }
+
+        Some(index)
+    }
+
+    // Walks towards both ends at once and stops at whichever is reached first.
+    fn slot_position(&self, index: usize) -> usize {
+        let mut prev = self.prev_of(index);
+        let mut next = self.next_of(index);
+        let mut steps = 0;
+
+        loop {
+            if prev == SENTINEL {
+                return steps;
+            }
+
+            if next == SENTINEL {
+                return self.count() - 1 - steps;
+            }
+
+            prev = self.prev_of(prev);
+            next = self.next_of(next);
+            steps += 1;
+        }
+    }
+
+    // Moves the sentinel so that it sits just before `index`.
+    fn rotate_to_slot(&mut self, index: usize) {
+        if index == self.ends().0 {
+            return;
+        }
+
+        let prev = self.prev_of(index);
+
+        self.unlink(SENTINEL);
+        self.set_links(SENTINEL, prev, index);
+    }
+
+    fn reverse_links(&mut self) {
+        let mut index = SENTINEL;
+
+        loop {
+            let (prev, next) = (self.prev_of(index), self.next_of(index));
+
+            *self.prev_mut(index) = next;
+            *self.next_mut(index) = prev;
+
+            index = next;
+
+            if index == SENTINEL {
+                break;
+            }
+        }
+    }
+
+    fn swap_slots(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+
+        let (a_prev, a_next) = (self.prev_of(a), self.next_of(a));
+        let (b_prev, b_next) = (self.prev_of(b), self.next_of(b));
+
+        if a_next == b {
+            self.set_links(b, a_prev, a);
+            self.set_links(a, b, b_next);
+        } else if b_next == a {
+            self.set_links(a, b_prev, b);
+            self.set_links(b, a, a_next);
+        } else {
+            self.set_links(a, b_prev, b_next);
+            self.set_links(b, a_prev, a_next);
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use core::marker::PhantomData;
+use core::num::NonZeroU32;
+
+use alloc::vec::Vec;
+
+use super::{DlEntry, DlError, ListId, Provenance};
+
+// A slot number stored as `slot + 1`, so that `None` costs no extra space.
+type Link = Option<NonZeroU32>;
+
+fn link(index: usize) -> Link {
+    NonZeroU32::new(index as u32 + 1)
+}
+
+fn slot(link: NonZeroU32) -> usize {
+    link.get() as usize - 1
+}
+
+// A generation stored as `rm_cnt + 1`. Its zero niche holds the `DlEntry`
+// tag, so a free slot costs no more than an occupied one.
+fn stamp(rm_cnt: u32) -> NonZeroU32 {
+    NonZeroU32::new(rm_cnt + 1).unwrap()
+}
+
+/// A `DlList` with 32-bit slots and generations.
+///
+/// Each slot carries 12 bytes of links and generation on top of its item, with
+/// no separate tag for free slots, so a slot of `DlList32<u64>` takes 24 bytes
+/// against 40 in `DlList<u64>`. `Option<DlIndex32<T>>` is no larger than
+/// `DlIndex32<T>`. The list holds at most `u32::MAX` slots and hands out at
+/// most `u32::MAX` generations; once either is used up, `push_*` returns the
+/// item instead of wrapping around.
+#[derive(Debug, PartialEq)]
+pub struct DlList32<T> {
+    data: Vec<DlEntry<OcEntry32<T>, Link>>,
+    next_free: Link,
+    head: Link,
+    tail: Link,
+    rm_cnt: u32,
+    len: u32,
+    list_id: Provenance,
+}
+
+#[derive(Debug, PartialEq)]
+struct OcEntry32<T> {
+    item: T,
+    next: Link,
+    prev: Link,
+    rm_cnt: NonZeroU32,
+}
+
+/// A handle into a `DlList32`. It is 8 bytes in release builds without the
+/// `provenance` feature; otherwise it also carries the issuing list's id.
+#[derive(Debug)]
+pub struct DlIndex32<T> {
+    index: NonZeroU32,
+    rm_cnt: u32,
+    list_id: ListId,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Clone for DlIndex32<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for DlIndex32<T> {}
+
+impl<T> PartialEq for DlIndex32<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index && self.rm_cnt == other.rm_cnt && self.list_id == other.list_id
+    }
+}
+
+impl<T> Eq for DlIndex32<T> {}
+
+impl<T> core::hash::Hash for DlIndex32<T> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+        self.rm_cnt.hash(state);
+        self.list_id.hash(state);
+    }
+}
+
+impl<T> DlIndex32<T> {
+    fn new(index: NonZeroU32, rm_cnt: u32, list_id: ListId) -> DlIndex32<T> {
+        DlIndex32 {
+            index,
+            rm_cnt,
+            list_id,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for DlList32<T> {
+    fn default() -> Self {
+        DlList32 {
+            data: Default::default(),
+            next_free: Default::default(),
+            head: Default::default(),
+            tail: Default::default(),
+            rm_cnt: Default::default(),
+            len: Default::default(),
+            list_id: Provenance(ListId::new()),
+        }
+    }
+}
+
+impl<T> DlList32<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    // Slot `u32::MAX` would need link `u32::MAX + 1`, so the last slot is
+    // `u32::MAX - 1`.
+    const MAX_SLOTS: usize = u32::MAX as usize;
+
+    #[allow(dead_code)]
+    pub fn new() -> DlList32<T> {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[allow(dead_code)]
+    pub fn head_index(&self) -> Option<DlIndex32<T>> {
+        self.head.map(|index| self.slot_index(index))
+    }
+
+    #[allow(dead_code)]
+    pub fn tail_index(&self) -> Option<DlIndex32<T>> {
+        self.tail.map(|index| self.slot_index(index))
+    }
+
+    /// Appends `item`, or returns it if the list has run out of slots or
+    /// generations.
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> Result<DlIndex32<T>, T> {
+        let tail = self.tail;
+        let index = self.alloc(item, tail, None)?;
+
+        match tail {
+            Some(tail) => self.occupied_mut(tail).next = Some(index),
+            None => self.head = Some(index),
+        }
+
+        self.tail = Some(index);
+
+        Ok(self.slot_index(index))
+    }
+
+    /// Prepends `item`, or returns it if the list has run out of slots or
+    /// generations.
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> Result<DlIndex32<T>, T> {
+        let head = self.head;
+        let index = self.alloc(item, None, head)?;
+
+        match head {
+            Some(head) => self.occupied_mut(head).prev = Some(index),
+            None => self.tail = Some(index),
+        }
+
+        self.head = Some(index);
+
+        Ok(self.slot_index(index))
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, value: &T) -> bool {
+        self.iter().any(|e| e == value)
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex32<T>) -> Option<&T> {
+        self.try_get(index).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn try_get(&self, index: DlIndex32<T>) -> Result<&T, DlError> {
+        Ok(&self.check(index)?.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, index: DlIndex32<T>) -> Option<&mut T> {
+        self.check(index).ok()?;
+
+        Some(&mut self.occupied_mut(index.index).item)
+    }
+
+    #[allow(dead_code)]
+    pub fn next_index(&self, index: DlIndex32<T>) -> Option<DlIndex32<T>> {
+        let next = self.check(index).ok()?.next?;
+
+        Some(self.slot_index(next))
+    }
+
+    #[allow(dead_code)]
+    pub fn prev_index(&self, index: DlIndex32<T>) -> Option<DlIndex32<T>> {
+        let prev = self.check(index).ok()?.prev?;
+
+        Some(self.slot_index(prev))
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex32<T>) -> Option<T> {
+        self.try_remove(index).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn try_remove(&mut self, index: DlIndex32<T>) -> Result<T, DlError> {
+        let (prev, next) = {
+            let e = self.check(index)?;
+
+            (e.prev, e.next)
+        };
+
+        match prev {
+            Some(prev) => self.occupied_mut(prev).next = next,
+            None => self.head = next,
+        }
+
+        match next {
+            Some(next) => self.occupied_mut(next).prev = prev,
+            None => self.tail = prev,
+        }
+
+        let removed = core::mem::replace(
+            &mut self.data[slot(index.index)],
+            DlEntry::Free {
+                next_free: self.next_free,
+            },
+        );
+
+        self.next_free = Some(index.index);
+
+        // Once the counter saturates no further items are inserted, so a
+        // generation is never handed out twice.
+        self.rm_cnt = self.rm_cnt.saturating_add(1);
+        self.len -= 1;
+
+        match removed {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => Ok(e.item),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head = self.head_index()?;
+
+        self.remove(head)
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut next = self.head;
+
+        core::iter::from_fn(move || {
+            let entry = self.occupied(next?);
+
+            next = entry.next;
+
+            Some(&entry.item)
+        })
+    }
+
+    fn alloc(&mut self, item: T, prev: Link, next: Link) -> Result<NonZeroU32, T> {
+        if self.rm_cnt == u32::MAX {
+            return Err(item);
+        }
+
+        let entry = OcEntry32 {
+            item,
+            next,
+            prev,
+            rm_cnt: stamp(self.rm_cnt),
+        };
+
+        let index = if let Some(index) = self.next_free {
+            match self.data[slot(index)] {
+                DlEntry::Occupied { .. } => panic!("list corrupted!"),
+                DlEntry::Free { next_free } => self.next_free = next_free,
+            }
+
+            self.data[slot(index)] = DlEntry::Occupied(entry);
+
+            index
+        } else {
+            if self.data.len() >= Self::MAX_SLOTS {
+                return Err(entry.item);
+            }
+
+            self.data.push(DlEntry::Occupied(entry));
+
+            link(self.data.len() - 1).unwrap()
+        };
+
+        self.len += 1;
+
+        Ok(index)
+    }
+
+    fn check(&self, index: DlIndex32<T>) -> Result<&OcEntry32<T>, DlError> {
+        if index.list_id != self.list_id.0 {
+            return Err(DlError::ForeignIndex);
+        }
+
+        match self.data.get(slot(index.index)) {
+            Some(DlEntry::Occupied(e)) if e.rm_cnt.get() - 1 == index.rm_cnt => Ok(e),
+            _ => Err(DlError::StaleIndex),
+        }
+    }
+
+    fn slot_index(&self, index: NonZeroU32) -> DlIndex32<T> {
+        DlIndex32::new(index, self.occupied(index).rm_cnt.get() - 1, self.list_id.0)
+    }
+
+    fn occupied(&self, index: NonZeroU32) -> &OcEntry32<T> {
+        match &self.data[slot(index)] {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e,
+        }
+    }
+
+    fn occupied_mut(&mut self, index: NonZeroU32) -> &mut OcEntry32<T> {
+        match &mut self.data[slot(index)] {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e,
+        }
+    }
+}
+
+impl<T> core::ops::Index<DlIndex32<T>> for DlList32<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex32<T>) -> &Self::Output {
+        self.try_get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(size_of::<OcEntry32<()>>(), 12);
+        assert_eq!(size_of::<DlEntry<OcEntry32<()>, Link>>(), 12);
+        assert_eq!(size_of::<DlEntry<OcEntry32<u64>, Link>>(), 24);
+        assert_eq!(size_of::<DlEntry<super::super::OcEntry<u64>>>(), 40);
+        assert_eq!(size_of::<DlIndex32<()>>(), 8 + size_of::<ListId>());
+        assert_eq!(
+            size_of::<Option<DlIndex32<()>>>(),
+            size_of::<DlIndex32<()>>()
+        );
+    }
+
+    #[test]
+    fn push_and_remove() {
+        let mut list = DlList32::new();
+
+        let one = list.push_back(1).unwrap();
+        let two = list.push_back(2).unwrap();
+        let zero = list.push_front(0).unwrap();
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(list.next_index(zero), Some(one));
+        assert_eq!(list.prev_index(two), Some(one));
+        assert_eq!(list.head_index(), Some(zero));
+        assert_eq!(list.tail_index(), Some(two));
+
+        assert_eq!(list.remove(one), Some(1));
+        assert_eq!(list.get(one), None);
+        assert_eq!(list.next_index(zero), Some(two));
+
+        let three = list.push_back(3).unwrap();
+        assert_eq!(three.index, one.index);
+        assert_ne!(three, one);
+
+        *list.get_mut(three).unwrap() += 1;
+        assert_eq!(list[three], 4);
+
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.len(), 2);
+        assert!(list.contains(&2));
+    }
+
+    #[test]
+    fn generations_exhausted() {
+        let mut list = DlList32::new();
+
+        list.rm_cnt = u32::MAX - 1;
+
+        let one = list.push_back(1).unwrap();
+        assert_eq!(list.remove(one), Some(1));
+
+        assert_eq!(list.push_back(2), Err(2));
+        assert_eq!(list.push_front(3), Err(3));
+        assert!(list.is_empty());
+    }
+}
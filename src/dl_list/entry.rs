@@ -0,0 +1,306 @@
+use super::{Chain, DlError, DlIndex, DlList, SENTINEL};
+
+/// A view into a single handle's slot, returned by `DlList::entry`.
+#[derive(Debug)]
+pub enum Entry<'a, T>
+where
+    T: 'a,
+{
+    /// The handle refers to an item in the list.
+    Occupied(OccupiedEntry<'a, T>),
+    /// The handle is stale or belongs to another list.
+    Stale(DlError),
+}
+
+/// A validated handle together with the list it belongs to.
+#[derive(Debug)]
+pub struct OccupiedEntry<'a, T>
+where
+    T: 'a,
+{
+    list: &'a mut DlList<T>,
+    index: DlIndex<T>,
+}
+
+impl<'a, T> Entry<'a, T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    pub(super) fn new(list: &'a mut DlList<T>, index: DlIndex<T>) -> Entry<'a, T> {
+        match list.check(index) {
+            Ok(_) => Entry::Occupied(OccupiedEntry { list, index }),
+            Err(e) => Entry::Stale(e),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_occupied(&self) -> bool {
+        match self {
+            Entry::Occupied(_) => true,
+            Entry::Stale(_) => false,
+        }
+    }
+
+    /// Returns the occupied entry, or why the handle could not be used.
+    #[allow(dead_code)]
+    pub fn occupied(self) -> Result<OccupiedEntry<'a, T>, DlError> {
+        match self {
+            Entry::Occupied(e) => Ok(e),
+            Entry::Stale(e) => Err(e),
+        }
+    }
+}
+
+impl<'a, T> OccupiedEntry<'a, T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn index(&self) -> DlIndex<T> {
+        self.index
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self) -> &T {
+        &self.list.occupied(self.index.index).item
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.list.occupied_mut(self.index.index).item
+    }
+
+    #[allow(dead_code)]
+    pub fn into_mut(self) -> &'a mut T {
+        &mut self.list.occupied_mut(self.index.index).item
+    }
+
+    /// Replaces the item in place, returning the old one. The handle stays
+    /// valid.
+    #[allow(dead_code)]
+    pub fn insert(&mut self, item: T) -> T {
+        core::mem::replace(self.get_mut(), item)
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(self) -> T {
+        let item = self.list.remove_slot(self.index.index);
+
+        self.list.auto_trim();
+
+        item
+    }
+
+    #[allow(dead_code)]
+    pub fn move_to_front(&mut self) {
+        let index = self.index.index;
+
+        self.list.unlink(index);
+
+        let head = self.list.head;
+        self.list.set_links(index, SENTINEL, head);
+    }
+
+    #[allow(dead_code)]
+    pub fn move_to_back(&mut self) {
+        let index = self.index.index;
+
+        self.list.unlink(index);
+
+        let tail = self.list.tail;
+        self.list.set_links(index, tail, SENTINEL);
+    }
+
+    #[allow(dead_code)]
+    pub fn next_index(&self) -> Option<DlIndex<T>> {
+        self.list.next_index(self.index)
+    }
+
+    #[allow(dead_code)]
+    pub fn prev_index(&self) -> Option<DlIndex<T>> {
+        self.list.prev_index(self.index)
+    }
+}
+
+/// A slot reserved by `DlList::vacant_entry`. Its handle is known up front,
+/// but the slot is only taken off the free chain and linked into the list
+/// when the entry is filled.
+#[derive(Debug)]
+pub struct VacantEntry<'a, T>
+where
+    T: 'a,
+{
+    list: &'a mut DlList<T>,
+    index: DlIndex<T>,
+}
+
+impl<'a, T> VacantEntry<'a, T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    pub(super) fn new(list: &'a mut DlList<T>) -> VacantEntry<'a, T> {
+        let index = DlIndex::new(list.next_slot(), list.rm_cnt, list.list_id.0);
+
+        VacantEntry { list, index }
+    }
+
+    /// The handle the item will be stored under once the entry is filled.
+    #[allow(dead_code)]
+    pub fn index(&self) -> DlIndex<T> {
+        self.index
+    }
+
+    #[allow(dead_code)]
+    pub fn push_back(self, item: T) -> DlIndex<T> {
+        let index = self.list.push_back(item);
+
+        debug_assert_eq!(index, self.index);
+
+        index
+    }
+
+    #[allow(dead_code)]
+    pub fn push_front(self, item: T) -> DlIndex<T> {
+        let index = self.list.push_front(item);
+
+        debug_assert_eq!(index, self.index);
+
+        index
+    }
+
+    #[allow(dead_code)]
+    pub fn insert_after(self, after: DlIndex<T>, item: T) -> Result<DlIndex<T>, DlError> {
+        let index = self.list.insert_after(after, item)?;
+
+        debug_assert_eq!(index, self.index);
+
+        Ok(index)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_back_with<F>(self, f: F) -> DlIndex<T>
+    where
+        F: FnOnce(DlIndex<T>) -> T,
+    {
+        let item = f(self.index);
+
+        self.push_back(item)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_front_with<F>(self, f: F) -> DlIndex<T>
+    where
+        F: FnOnce(DlIndex<T>) -> T,
+    {
+        let item = f(self.index);
+
+        self.push_front(item)
+    }
+
+    #[allow(dead_code)]
+    pub fn insert_after_with<F>(self, after: DlIndex<T>, f: F) -> Result<DlIndex<T>, DlError>
+    where
+        F: FnOnce(DlIndex<T>) -> T,
+    {
+        self.list.check(after)?;
+
+        let item = f(self.index);
+
+        self.insert_after(after, item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vacant_entry() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+
+        let reserved = list.vacant_entry().index();
+
+        assert_eq!(list.len(), 1);
+        assert!(list.get(reserved).is_none());
+
+        let entry = list.vacant_entry();
+        assert_eq!(entry.index(), reserved);
+        assert_eq!(entry.insert_after(one, 2), Ok(reserved));
+
+        assert_eq!(list[reserved], 2);
+        assert_eq!(list.tail_index(), Some(reserved));
+    }
+
+    #[test]
+    fn vacant_entry_reuses_free_slot() {
+        let mut list = DlList::new();
+
+        list.push_back(1);
+        let two = list.push_back(2);
+        list.remove(two);
+
+        let entry = list.vacant_entry();
+        let reserved = entry.index();
+
+        assert_eq!(reserved.index, two.index);
+        assert_ne!(reserved, two);
+        assert_eq!(entry.push_front(0), reserved);
+        assert_eq!(list.head_index(), Some(reserved));
+    }
+
+    #[test]
+    fn insert_after_with_stale() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        list.remove(one);
+
+        let result = list.vacant_entry().insert_after_with(one, |_| -> i32 {
+            panic!("must not be called");
+        });
+
+        assert_eq!(result, Err(DlError::StaleIndex));
+    }
+
+    #[test]
+    fn entry() {
+        let mut list = DlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        {
+            let mut entry = list.entry(two).occupied().unwrap();
+
+            assert_eq!(entry.get(), &2);
+            *entry.get_mut() += 20;
+            assert_eq!(entry.insert(200), 22);
+            assert_eq!(entry.prev_index(), Some(one));
+            assert_eq!(entry.next_index(), Some(three));
+
+            entry.move_to_back();
+            assert_eq!(entry.next_index(), None);
+
+            entry.move_to_front();
+            assert_eq!(entry.prev_index(), None);
+        }
+
+        assert_eq!(list.head_index(), Some(two));
+        assert_eq!(list[two], 200);
+
+        match list.entry(two) {
+            Entry::Occupied(e) if *e.get() > 100 => assert_eq!(e.remove(), 200),
+            _ => panic!("expected an occupied entry"),
+        }
+
+        assert!(!list.entry(two).is_occupied());
+        assert_eq!(list.entry(two).occupied().err(), Some(DlError::StaleIndex));
+        assert_eq!(list.len(), 2);
+    }
+}
@@ -0,0 +1,406 @@
+use alloc::vec::Vec;
+
+use super::{Chain, DlIndex, DlList};
+
+/// A `DlList` paired with an order-statistic tree over its slots, so that
+/// positional operations run in O(log n) expected time.
+///
+/// Handles are plain `DlIndex<T>` values with the same generation semantics
+/// as `DlList`; `list()` exposes the underlying list for everything that is
+/// not position-based.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct IndexedDlList<T> {
+    list: DlList<T>,
+    nodes: Vec<TreeNode>,
+    root: Option<usize>,
+    seed: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct TreeNode {
+    left: Option<usize>,
+    right: Option<usize>,
+    parent: Option<usize>,
+    size: usize,
+    priority: u64,
+}
+
+impl<T> Default for IndexedDlList<T> {
+    fn default() -> Self {
+        IndexedDlList {
+            list: Default::default(),
+            nodes: Default::default(),
+            root: Default::default(),
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl<T> IndexedDlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> IndexedDlList<T> {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn list(&self) -> &DlList<T> {
+        &self.list
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex<T>) -> Option<&T> {
+        self.list.get(index)
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.list.iter()
+    }
+
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> DlIndex<T> {
+        let pos = self.len();
+        let index = self.list.push_back(item);
+
+        self.tree_insert(index.index, pos);
+
+        index
+    }
+
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> DlIndex<T> {
+        let index = self.list.push_front(item);
+
+        self.tree_insert(index.index, 0);
+
+        index
+    }
+
+    /// Returns the item at position `pos`. O(log n).
+    #[allow(dead_code)]
+    pub fn nth(&self, pos: usize) -> Option<&T> {
+        let index = self.tree_nth(pos)?;
+
+        Some(&self.list.occupied(index).item)
+    }
+
+    /// Returns the handle of the item at position `pos`. O(log n).
+    #[allow(dead_code)]
+    pub fn nth_index(&self, pos: usize) -> Option<DlIndex<T>> {
+        let index = self.tree_nth(pos)?;
+
+        self.list.handle(index)
+    }
+
+    /// Returns the position of `index`, or `None` if the handle is stale.
+    /// O(log n).
+    #[allow(dead_code)]
+    pub fn position_of(&self, index: DlIndex<T>) -> Option<usize> {
+        self.list.get(index)?;
+
+        Some(self.tree_position(index.index))
+    }
+
+    /// Inserts `item` so that it ends up at position `pos`. O(log n).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos > len`.
+    #[allow(dead_code)]
+    pub fn insert_at(&mut self, pos: usize, item: T) -> DlIndex<T> {
+        let len = self.len();
+
+        assert!(
+            pos <= len,
+            "insertion position (is {}) should be <= len (is {})",
+            pos,
+            len
+        );
+
+        if pos == len {
+            return self.push_back(item);
+        }
+
+        let next_index = self.tree_nth(pos).unwrap();
+        let index = self.list.link_before(next_index, item);
+
+        self.tree_insert(index.index, pos);
+
+        index
+    }
+
+    /// Removes the item addressed by `index`. O(log n).
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex<T>) -> Option<T> {
+        let pos = self.position_of(index)?;
+
+        self.tree_remove(pos);
+
+        self.list.remove(index)
+    }
+
+    /// Removes and returns the item at position `pos`. O(log n).
+    #[allow(dead_code)]
+    pub fn remove_at(&mut self, pos: usize) -> Option<T> {
+        let index = self.nth_index(pos)?;
+
+        self.remove(index)
+    }
+
+    #[allow(dead_code)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove_at(0)
+    }
+
+    fn size(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |node| self.nodes[node].size)
+    }
+
+    fn update(&mut self, node: usize) {
+        let TreeNode { left, right, .. } = self.nodes[node];
+
+        self.nodes[node].size = 1 + self.size(left) + self.size(right);
+
+        for child in left.into_iter().chain(right) {
+            self.nodes[child].parent = Some(node);
+        }
+    }
+
+    fn next_priority(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+
+        self.seed
+    }
+
+    // Splits off the first `k` nodes of `node` into the left tree.
+    fn split(&mut self, node: Option<usize>, k: usize) -> (Option<usize>, Option<usize>) {
+        let node = match node {
+            Some(node) => node,
+            None => return (None, None),
+        };
+
+        let (left, right) = if self.size(self.nodes[node].left) >= k {
+            let (left, right) = self.split(self.nodes[node].left, k);
+            self.nodes[node].left = right;
+            (left, Some(node))
+        } else {
+            let k = k - self.size(self.nodes[node].left) - 1;
+            let (left, right) = self.split(self.nodes[node].right, k);
+            self.nodes[node].right = left;
+            (Some(node), right)
+        };
+
+        self.update(node);
+
+        for root in left.into_iter().chain(right) {
+            self.nodes[root].parent = None;
+        }
+
+        (left, right)
+    }
+
+    fn merge(&mut self, left: Option<usize>, right: Option<usize>) -> Option<usize> {
+        let (left, right) = match (left, right) {
+            (None, tree) | (tree, None) => return tree,
+            (Some(left), Some(right)) => (left, right),
+        };
+
+        let root = if self.nodes[left].priority > self.nodes[right].priority {
+            self.nodes[left].right = self.merge(self.nodes[left].right, Some(right));
+            left
+        } else {
+            self.nodes[right].left = self.merge(Some(left), self.nodes[right].left);
+            right
+        };
+
+        self.update(root);
+        self.nodes[root].parent = None;
+
+        Some(root)
+    }
+
+    fn tree_insert(&mut self, index: usize, pos: usize) {
+        if self.nodes.len() <= index {
+            self.nodes.resize(index + 1, TreeNode::default());
+        }
+
+        self.nodes[index] = TreeNode {
+            size: 1,
+            priority: self.next_priority(),
+            ..TreeNode::default()
+        };
+
+        let (left, right) = self.split(self.root, pos);
+        let left = self.merge(left, Some(index));
+
+        self.root = self.merge(left, right);
+    }
+
+    fn tree_remove(&mut self, pos: usize) {
+        let (left, right) = self.split(self.root, pos);
+        let (_, right) = self.split(right, 1);
+
+        self.root = self.merge(left, right);
+    }
+
+    fn tree_nth(&self, mut pos: usize) -> Option<usize> {
+        let mut node = self.root?;
+
+        loop {
+            let left = self.size(self.nodes[node].left);
+
+            if pos < left {
+                node = self.nodes[node].left?;
+            } else if pos == left {
+                return Some(node);
+            } else {
+                pos -= left + 1;
+                node = self.nodes[node].right?;
+            }
+        }
+    }
+
+    fn tree_position(&self, mut node: usize) -> usize {
+        let mut pos = self.size(self.nodes[node].left);
+
+        while let Some(parent) = self.nodes[node].parent {
+            if self.nodes[parent].right == Some(node) {
+                pos += self.size(self.nodes[parent].left) + 1;
+            }
+
+            node = parent;
+        }
+
+        pos
+    }
+}
+
+impl<T> core::ops::Index<DlIndex<T>> for IndexedDlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.list.try_get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push() {
+        let mut list = IndexedDlList::new();
+
+        let two = list.push_back(2);
+        let one = list.push_front(1);
+        let three = list.push_back(3);
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(list.position_of(one), Some(0));
+        assert_eq!(list.position_of(two), Some(1));
+        assert_eq!(list.position_of(three), Some(2));
+    }
+
+    #[test]
+    fn nth() {
+        let mut list = IndexedDlList::new();
+
+        for i in 0..100 {
+            list.push_back(i);
+        }
+
+        for i in 0..100 {
+            assert_eq!(list.nth(i).unwrap(), &i);
+            assert_eq!(list[list.nth_index(i).unwrap()], i);
+        }
+
+        assert!(list.nth(100).is_none());
+    }
+
+    #[test]
+    fn insert_at() {
+        let mut list = IndexedDlList::new();
+
+        list.insert_at(0, 2);
+        list.insert_at(0, 0);
+        list.insert_at(1, 1);
+        list.insert_at(3, 4);
+        let three = list.insert_at(3, 3);
+
+        assert_eq!(
+            list.iter().copied().collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(list.position_of(three), Some(3));
+    }
+
+    #[test]
+    fn remove() {
+        let mut list = IndexedDlList::new();
+
+        let indexes: Vec<_> = (0..5).map(|i| list.push_back(i)).collect();
+
+        assert_eq!(list.remove(indexes[2]), Some(2));
+        assert_eq!(list.remove(indexes[2]), None);
+        assert_eq!(list.position_of(indexes[2]), None);
+        assert_eq!(list.position_of(indexes[3]), Some(2));
+        assert_eq!(list.remove_at(0), Some(0));
+        assert_eq!(list.pop_front(), Some(1));
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn matches_vec() {
+        let mut list = IndexedDlList::new();
+        let mut model: Vec<(u32, DlIndex<u32>)> = Vec::new();
+        let mut seed = 7u32;
+
+        for i in 0..2000 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let r = (seed >> 8) as usize;
+
+            if r.is_multiple_of(3) && !model.is_empty() {
+                let pos = r % model.len();
+                let (value, index) = model.remove(pos);
+
+                assert_eq!(list.position_of(index), Some(pos));
+                assert_eq!(list.remove(index), Some(value));
+            } else {
+                let pos = r % (model.len() + 1);
+                let index = list.insert_at(pos, i);
+
+                model.insert(pos, (i, index));
+            }
+        }
+
+        for (pos, (value, index)) in model.iter().enumerate() {
+            assert_eq!(list.nth(pos), Some(value));
+            assert_eq!(list.position_of(*index), Some(pos));
+        }
+
+        assert_eq!(list.len(), model.len());
+    }
+}
@@ -0,0 +1,320 @@
+use super::{Chain, DlEntry, DlError, DlIndex, DlList, SENTINEL};
+
+/// A circular view over a `DlList`: the tail is followed by the head and the
+/// head is preceded by the tail, and a `current` cursor can be advanced around
+/// the ring indefinitely.
+#[derive(Debug)]
+#[allow(dead_code)]
+pub struct RingDlList<T> {
+    list: DlList<T>,
+    current: Option<usize>,
+}
+
+impl<T> Default for RingDlList<T> {
+    fn default() -> Self {
+        RingDlList {
+            list: Default::default(),
+            current: Default::default(),
+        }
+    }
+}
+
+impl<T> RingDlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> RingDlList<T> {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn list(&self) -> &DlList<T> {
+        &self.list
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex<T>) -> Option<&T> {
+        self.list.get(index)
+    }
+
+    /// Inserts `item` at the tail, i.e. just before the head in ring order.
+    /// The first item pushed becomes `current`.
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> DlIndex<T> {
+        let index = self.list.push_back(item);
+
+        self.current.get_or_insert(index.index);
+
+        index
+    }
+
+    /// Inserts `item` at the head. The first item pushed becomes `current`.
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> DlIndex<T> {
+        let index = self.list.push_front(item);
+
+        self.current.get_or_insert(index.index);
+
+        index
+    }
+
+    /// Removes the item addressed by `index`. If it was `current`, the cursor
+    /// moves on to the next item in ring order.
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex<T>) -> Option<T> {
+        self.list.get(index)?;
+
+        if self.current == Some(index.index) {
+            self.current = if self.list.len() == 1 {
+                None
+            } else {
+                Some(self.next_slot(index.index))
+            };
+        }
+
+        self.list.remove(index)
+    }
+
+    /// Like `DlList::next_index`, but the tail wraps around to the head.
+    #[allow(dead_code)]
+    pub fn next_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        self.list.get(index)?;
+
+        Some(self.slot_index(self.next_slot(index.index)))
+    }
+
+    /// Like `DlList::prev_index`, but the head wraps around to the tail.
+    #[allow(dead_code)]
+    pub fn prev_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        self.list.get(index)?;
+
+        Some(self.slot_index(self.prev_slot(index.index)))
+    }
+
+    #[allow(dead_code)]
+    pub fn current(&self) -> Option<&T> {
+        let index = self.current?;
+
+        Some(&self.list.occupied(index).item)
+    }
+
+    #[allow(dead_code)]
+    pub fn current_index(&self) -> Option<DlIndex<T>> {
+        let index = self.current?;
+
+        Some(self.slot_index(index))
+    }
+
+    /// Moves the cursor to `index`.
+    #[allow(dead_code)]
+    pub fn set_current(&mut self, index: DlIndex<T>) -> Result<(), DlError> {
+        self.list.check(index)?;
+
+        self.current = Some(index.index);
+
+        Ok(())
+    }
+
+    /// Moves the cursor one step forward, wrapping from the tail to the head,
+    /// and returns the new current item.
+    #[allow(dead_code)]
+    pub fn advance(&mut self) -> Option<&T> {
+        let index = self.next_slot(self.current?);
+
+        self.current = Some(index);
+
+        Some(&self.list.occupied(index).item)
+    }
+
+    /// Moves the cursor one step backward, wrapping from the head to the
+    /// tail, and returns the new current item.
+    #[allow(dead_code)]
+    pub fn retreat(&mut self) -> Option<&T> {
+        let index = self.prev_slot(self.current?);
+
+        self.current = Some(index);
+
+        Some(&self.list.occupied(index).item)
+    }
+
+    /// Visits every item exactly once in ring order, starting at `current`.
+    #[allow(dead_code)]
+    pub fn iter(&self) -> RingIter<'_, T> {
+        RingIter {
+            list: &self.list,
+            next_index: self.current,
+            remaining: self.list.len(),
+        }
+    }
+
+    /// Visits every item exactly once in ring order, starting at `index`.
+    /// Yields nothing if the handle is stale.
+    #[allow(dead_code)]
+    pub fn iter_from(&self, index: DlIndex<T>) -> RingIter<'_, T> {
+        let remaining = match self.list.get(index) {
+            Some(_) => self.list.len(),
+            None => 0,
+        };
+
+        RingIter {
+            list: &self.list,
+            next_index: Some(index.index),
+            remaining,
+        }
+    }
+
+    fn next_slot(&self, index: usize) -> usize {
+        match self.list.next_of(index) {
+            SENTINEL => self.list.head,
+            next => next,
+        }
+    }
+
+    fn prev_slot(&self, index: usize) -> usize {
+        match self.list.prev_of(index) {
+            SENTINEL => self.list.tail,
+            prev => prev,
+        }
+    }
+
+    fn slot_index(&self, index: usize) -> DlIndex<T> {
+        DlIndex::new(index, self.list.occupied(index).rm_cnt, self.list.list_id.0)
+    }
+}
+
+impl<T> core::ops::Index<DlIndex<T>> for RingDlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.list.try_get(index).unwrap()
+    }
+}
+
+pub struct RingIter<'a, T>
+where
+    T: 'a,
+{
+    list: &'a DlList<T>,
+    next_index: Option<usize>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for RingIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let index = self.next_index?;
+
+        match &self.list.data[index] {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => {
+                self.next_index = Some(match e.next {
+                    SENTINEL => self.list.head,
+                    next => next,
+                });
+                self.remaining -= 1;
+
+                Some(&e.item)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_index_wraps() {
+        let mut list = RingDlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        assert_eq!(list.next_index(two), Some(three));
+        assert_eq!(list.next_index(three), Some(one));
+        assert_eq!(list.prev_index(one), Some(three));
+
+        list.remove(two);
+        assert_eq!(list.next_index(two), None);
+    }
+
+    #[test]
+    fn advance() {
+        let mut list = RingDlList::new();
+
+        assert!(list.advance().is_none());
+
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        assert_eq!(list.current(), Some(&1));
+        assert_eq!(list.advance(), Some(&2));
+        assert_eq!(list.advance(), Some(&3));
+        assert_eq!(list.advance(), Some(&1));
+        assert_eq!(list.retreat(), Some(&3));
+    }
+
+    #[test]
+    fn remove_current() {
+        let mut list = RingDlList::new();
+
+        list.push_back(1);
+        list.push_back(2);
+        let three = list.push_back(3);
+
+        assert!(list.set_current(three).is_ok());
+        assert_eq!(list.remove(three), Some(3));
+        assert_eq!(list.current(), Some(&1));
+
+        let one = list.current_index().unwrap();
+        list.remove(one);
+        let two = list.current_index().unwrap();
+        assert_eq!(list[two], 2);
+
+        list.remove(two);
+        assert!(list.current().is_none());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn iter() {
+        let mut list = RingDlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        list.push_back(3);
+        list.push_front(0);
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 0]);
+        assert_eq!(
+            list.iter_from(two).copied().collect::<Vec<_>>(),
+            vec![2, 3, 0, 1]
+        );
+
+        list.remove(one);
+        assert_eq!(list.iter_from(one).count(), 0);
+    }
+}
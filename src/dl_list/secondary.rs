@@ -0,0 +1,249 @@
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+
+use super::{DlIndex, DlList, ListId};
+
+/// Side-table data keyed by the handles of a `DlList<T>`.
+///
+/// Values live in a `Vec` indexed by slot and are tagged with the generation
+/// of the handle they were inserted under, so a value is never returned for a
+/// newer item that reuses its slot.
+#[derive(Debug)]
+pub struct DlSecondaryMap<T, V> {
+    slots: Vec<Option<SecondaryEntry<V>>>,
+    len: usize,
+    _marker: PhantomData<T>,
+}
+
+#[derive(Debug)]
+struct SecondaryEntry<V> {
+    value: V,
+    rm_cnt: usize,
+    list_id: ListId,
+}
+
+impl<T, V> Default for DlSecondaryMap<T, V> {
+    fn default() -> Self {
+        DlSecondaryMap {
+            slots: Default::default(),
+            len: Default::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, V> DlSecondaryMap<T, V> {
+    #[allow(dead_code)]
+    pub fn new() -> DlSecondaryMap<T, V> {
+        Self::default()
+    }
+
+    /// The number of values stored, including any whose primary item has
+    /// since been removed.
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Stores `value` for `index`, returning the value previously stored for
+    /// the same handle. A value left behind by an older item in the same
+    /// slot is dropped. If the slot already holds a value for a newer item of
+    /// the same list, `index` is stale: nothing changes and `value` is handed
+    /// back.
+    #[allow(dead_code)]
+    pub fn insert(&mut self, index: DlIndex<T>, value: V) -> Result<Option<V>, V> {
+        if self.slots.len() <= index.index {
+            self.slots.resize_with(index.index + 1, || None);
+        }
+
+        // Generations only grow within a list, so a newer entry wins.
+        if let Some(e) = &self.slots[index.index] {
+            if e.list_id == index.list_id && e.rm_cnt > index.rm_cnt {
+                return Err(value);
+            }
+        }
+
+        let old = self.slots[index.index].replace(SecondaryEntry {
+            value,
+            rm_cnt: index.rm_cnt,
+            list_id: index.list_id,
+        });
+
+        match old {
+            Some(e) if e.rm_cnt == index.rm_cnt && e.list_id == index.list_id => Ok(Some(e.value)),
+            Some(_) => Ok(None),
+            None => {
+                self.len += 1;
+                Ok(None)
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex<T>) -> Option<&V> {
+        match self.slots.get(index.index)? {
+            Some(e) if e.rm_cnt == index.rm_cnt && e.list_id == index.list_id => Some(&e.value),
+            _ => None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, index: DlIndex<T>) -> Option<&mut V> {
+        match self.slots.get_mut(index.index)? {
+            Some(e) if e.rm_cnt == index.rm_cnt && e.list_id == index.list_id => Some(&mut e.value),
+            _ => None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn contains_index(&self, index: DlIndex<T>) -> bool {
+        self.get(index).is_some()
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex<T>) -> Option<V> {
+        self.get(index)?;
+
+        self.len -= 1;
+
+        self.slots[index.index].take().map(|e| e.value)
+    }
+
+    /// Iterates over the stored values in slot order, with the handles they
+    /// were inserted under.
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = (DlIndex<T>, &V)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            slot.as_ref()
+                .map(|e| (DlIndex::new(index, e.rm_cnt, e.list_id), &e.value))
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(DlIndex<T>, &mut V) -> bool,
+    {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            let keep = match slot {
+                Some(e) => f(DlIndex::new(index, e.rm_cnt, e.list_id), &mut e.value),
+                None => true,
+            };
+
+            if !keep {
+                *slot = None;
+                self.len -= 1;
+            }
+        }
+    }
+}
+
+impl<T, V> DlSecondaryMap<T, V>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    /// Drops every value whose primary item is no longer in `list`.
+    #[allow(dead_code)]
+    pub fn retain_live(&mut self, list: &DlList<T>) {
+        self.retain(|index, _| list.contains_index(index));
+    }
+}
+
+impl<T, V> core::ops::Index<DlIndex<T>> for DlSecondaryMap<T, V> {
+    type Output = V;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_get_remove() {
+        let mut list = DlList::new();
+        let mut map = DlSecondaryMap::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+
+        assert_eq!(map.insert(two, "two"), Ok(None));
+        assert_eq!(map.insert(one, "one"), Ok(None));
+        assert_eq!(map.insert(one, "uno"), Ok(Some("one")));
+
+        assert_eq!(map[one], "uno");
+        assert_eq!(map.len(), 2);
+
+        *map.get_mut(two).unwrap() = "dos";
+        assert_eq!(map.remove(two), Some("dos"));
+        assert_eq!(map.remove(two), None);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn reused_slot() {
+        let mut list = DlList::new();
+        let mut map = DlSecondaryMap::new();
+
+        let one = list.push_back(1);
+        map.insert(one, "one").unwrap();
+
+        list.remove(one);
+        let two = list.push_back(2);
+
+        assert_eq!(two.index, one.index);
+        assert_eq!(map.get(two), None);
+        assert_eq!(map.insert(two, "two"), Ok(None));
+        assert_eq!(map.get(one), None);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn stale_insert() {
+        let mut list = DlList::new();
+        let mut map = DlSecondaryMap::new();
+
+        let one = list.push_back(1);
+        list.remove(one);
+        let two = list.push_back(2);
+
+        assert_eq!(two.index, one.index);
+        assert_eq!(map.insert(two, "two"), Ok(None));
+        assert_eq!(map.insert(one, "stale"), Err("stale"));
+        assert_eq!(map.get(two), Some(&"two"));
+        assert_eq!(map.get(one), None);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn iter() {
+        let mut list = DlList::new();
+        let mut map = DlSecondaryMap::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        map.insert(three, 30).unwrap();
+        map.insert(one, 10).unwrap();
+        map.insert(two, 20).unwrap();
+
+        list.remove(two);
+        map.retain_live(&list);
+
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![(one, &10), (three, &30)]
+        );
+        assert_eq!(map.len(), 2);
+    }
+}
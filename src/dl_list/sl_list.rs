@@ -0,0 +1,356 @@
+use alloc::vec::Vec;
+
+use super::{DlEntry, DlError, DlIndex, ListId, Provenance};
+
+/// A singly-linked counterpart to `DlList` for lists that are never walked
+/// backward. It uses the same slab, free chain and generation-checked
+/// `DlIndex<T>` handles, but each node stores only a `next` link.
+#[derive(Debug, PartialEq)]
+#[allow(dead_code)]
+pub struct SlList<T> {
+    data: Vec<DlEntry<SlEntry<T>>>,
+    next_free: Option<usize>,
+    head: Option<usize>,
+    tail: Option<usize>,
+    rm_cnt: usize,
+    len: usize,
+    list_id: Provenance,
+}
+
+#[derive(Debug, PartialEq)]
+struct SlEntry<T> {
+    item: T,
+    next: Option<usize>,
+    rm_cnt: usize,
+}
+
+impl<T> Default for SlList<T> {
+    fn default() -> Self {
+        SlList {
+            data: Default::default(),
+            next_free: Default::default(),
+            head: Default::default(),
+            tail: Default::default(),
+            rm_cnt: Default::default(),
+            len: Default::default(),
+            list_id: Provenance(ListId::new()),
+        }
+    }
+}
+
+impl<T> SlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> SlList<T> {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[allow(dead_code)]
+    pub fn head_index(&self) -> Option<DlIndex<T>> {
+        let index = self.head?;
+
+        Some(DlIndex::new(index, self.occupied(index).rm_cnt, self.list_id.0))
+    }
+
+    #[allow(dead_code)]
+    pub fn tail_index(&self) -> Option<DlIndex<T>> {
+        let index = self.tail?;
+
+        Some(DlIndex::new(index, self.occupied(index).rm_cnt, self.list_id.0))
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex<T>) -> Option<&T> {
+        self.try_get(index).ok()
+    }
+
+    /// Like `get`, but tells a stale handle apart from one that was issued
+    /// by a different list.
+    #[allow(dead_code)]
+    pub fn try_get(&self, index: DlIndex<T>) -> Result<&T, DlError> {
+        Ok(&self.check(index)?.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn next_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        self.get(index)?;
+
+        let next = self.occupied(index.index).next?;
+
+        Some(DlIndex::new(next, self.occupied(next).rm_cnt, self.list_id.0))
+    }
+
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> DlIndex<T> {
+        let rm_cnt = self.rm_cnt;
+
+        let position = self.alloc(SlEntry {
+            item,
+            next: None,
+            rm_cnt,
+        });
+
+        match self.tail {
+            Some(tail_index) => self.occupied_mut(tail_index).next = Some(position),
+            None => self.head = Some(position),
+        }
+
+        self.tail = Some(position);
+        self.len += 1;
+
+        DlIndex::new(position, rm_cnt, self.list_id.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> DlIndex<T> {
+        let rm_cnt = self.rm_cnt;
+
+        let position = self.alloc(SlEntry {
+            item,
+            next: self.head,
+            rm_cnt,
+        });
+
+        if self.tail.is_none() {
+            self.tail = Some(position);
+        }
+
+        self.head = Some(position);
+        self.len += 1;
+
+        DlIndex::new(position, rm_cnt, self.list_id.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head_index = self.head?;
+
+        let removed = self.free(head_index);
+
+        self.head = removed.next;
+
+        if self.head.is_none() {
+            self.tail = None;
+        }
+
+        Some(removed.item)
+    }
+
+    /// Removes the item following `index`. Returns `None` if the handle is
+    /// stale or `index` is the tail.
+    #[allow(dead_code)]
+    pub fn remove_after(&mut self, index: DlIndex<T>) -> Option<T> {
+        self.get(index)?;
+
+        let next_index = self.occupied(index.index).next?;
+
+        let removed = self.free(next_index);
+
+        self.occupied_mut(index.index).next = removed.next;
+
+        if removed.next.is_none() {
+            self.tail = Some(index.index);
+        }
+
+        Some(removed.item)
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut next_index = self.head;
+
+        core::iter::from_fn(move || {
+            let entry = self.occupied(next_index?);
+
+            next_index = entry.next;
+
+            Some(&entry.item)
+        })
+    }
+
+    fn alloc(&mut self, entry: SlEntry<T>) -> usize {
+        if let Some(position) = self.next_free {
+            match self.data[position] {
+                DlEntry::Occupied { .. } => panic!("list corrupted!"),
+                DlEntry::Free { next_free } => self.next_free = next_free,
+            }
+
+            self.data[position] = DlEntry::Occupied(entry);
+
+            position
+        } else {
+            self.data.push(DlEntry::Occupied(entry));
+
+            self.data.len() - 1
+        }
+    }
+
+    // Returns `index` to the free chain without touching any links.
+    fn free(&mut self, index: usize) -> SlEntry<T> {
+        let removed = core::mem::replace(
+            &mut self.data[index],
+            DlEntry::Free {
+                next_free: self.next_free,
+            },
+        );
+
+        self.next_free = Some(index);
+
+        self.rm_cnt += 1;
+        self.len -= 1;
+
+        match removed {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e,
+        }
+    }
+
+    fn check(&self, index: DlIndex<T>) -> Result<&SlEntry<T>, DlError> {
+        if index.list_id != self.list_id.0 {
+            return Err(DlError::ForeignIndex);
+        }
+
+        match self.data.get(index.index) {
+            Some(DlEntry::Occupied(e)) if e.rm_cnt == index.rm_cnt => Ok(e),
+            _ => Err(DlError::StaleIndex),
+        }
+    }
+
+    fn occupied(&self, index: usize) -> &SlEntry<T> {
+        match &self.data[index] {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e,
+        }
+    }
+
+    fn occupied_mut(&mut self, index: usize) -> &mut SlEntry<T> {
+        match &mut self.data[index] {
+            DlEntry::Free { .. } => panic!("list corrupted!"),
+            DlEntry::Occupied(e) => e,
+        }
+    }
+}
+
+impl<T> core::ops::Index<DlIndex<T>> for SlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.try_get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_back() {
+        let mut list = SlList::new();
+
+        list.push_back(1);
+        list.push_back(2);
+
+        assert_eq!(
+            list.data[0],
+            DlEntry::Occupied(SlEntry {
+                item: 1,
+                next: Some(1),
+                rm_cnt: 0,
+            })
+        );
+        assert_eq!(list.tail, Some(1));
+    }
+
+    #[test]
+    fn push_front() {
+        let mut list = SlList::new();
+
+        let one = list.push_front(1);
+        let zero = list.push_front(0);
+
+        assert_eq!(list.head_index(), Some(zero));
+        assert_eq!(list.tail_index(), Some(one));
+        assert_eq!(list.next_index(zero), Some(one));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn pop_front() {
+        let mut list = SlList::new();
+
+        list.push_back(1);
+        list.push_back(2);
+
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), None);
+
+        assert_eq!(
+            list,
+            SlList {
+                data: vec![
+                    DlEntry::Free { next_free: None },
+                    DlEntry::Free { next_free: Some(0) },
+                ],
+                next_free: Some(1),
+                head: None,
+                tail: None,
+                rm_cnt: 2,
+                len: 0,
+                list_id: Provenance(ListId::new()),
+            }
+        );
+    }
+
+    #[test]
+    fn remove_after() {
+        let mut list = SlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let three = list.push_back(3);
+
+        assert_eq!(list.remove_after(one), Some(2));
+        assert_eq!(list.get(two), None);
+        assert_eq!(list.remove_after(three), None);
+        assert_eq!(list.remove_after(two), None);
+
+        assert_eq!(list.remove_after(one), Some(3));
+        assert_eq!(list.tail_index(), Some(one));
+
+        let four = list.push_back(4);
+        assert_eq!(four, DlIndex::new(2, 2, list.list_id.0));
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 4]);
+    }
+
+    #[test]
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    fn foreign_index() {
+        let mut a = SlList::new();
+        let mut b = SlList::new();
+
+        let one = a.push_back(1);
+        b.push_back(1);
+
+        assert_eq!(b.try_get(one), Err(DlError::ForeignIndex));
+        assert_eq!(b.remove_after(one), None);
+        assert_eq!(a.try_get(one), Ok(&1));
+    }
+}
@@ -0,0 +1,634 @@
+use alloc::vec::Vec;
+
+use super::chain::Node;
+use super::{Chain, DlEntry, DlError, DlIndex, ListId, Provenance, SENTINEL};
+
+/// A `DlList` stored as a struct of arrays: the links and generations of all
+/// nodes live in one dense vector and the items in another.
+///
+/// Walking the list only touches the link vector, so traversal and removal
+/// no longer drag large items through the cache. It has `DlList`'s linking,
+/// positional and lookup operations with the same `DlIndex<T>` handles;
+/// slot policies, trimming, entries and mapping are left to `DlList`.
+#[derive(Debug, PartialEq)]
+#[allow(dead_code)]
+pub struct SoaDlList<T> {
+    links: Vec<DlEntry<Links>>,
+    items: Vec<Option<T>>,
+    next_free: Option<usize>,
+    head: usize,
+    tail: usize,
+    rm_cnt: usize,
+    len: usize,
+    list_id: Provenance,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct Links {
+    next: usize,
+    prev: usize,
+    rm_cnt: usize,
+}
+
+impl Node for Links {
+    fn next(&self) -> usize {
+        self.next
+    }
+
+    fn prev(&self) -> usize {
+        self.prev
+    }
+
+    fn next_mut(&mut self) -> &mut usize {
+        &mut self.next
+    }
+
+    fn prev_mut(&mut self) -> &mut usize {
+        &mut self.prev
+    }
+
+    fn rm_cnt(&self) -> usize {
+        self.rm_cnt
+    }
+}
+
+impl<T> Default for SoaDlList<T> {
+    fn default() -> Self {
+        SoaDlList {
+            links: Default::default(),
+            items: Default::default(),
+            next_free: Default::default(),
+            head: SENTINEL,
+            tail: SENTINEL,
+            rm_cnt: Default::default(),
+            len: Default::default(),
+            list_id: Provenance(ListId::new()),
+        }
+    }
+}
+
+impl<T> SoaDlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    #[allow(dead_code)]
+    pub fn new() -> SoaDlList<T> {
+        Self::default()
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[allow(dead_code)]
+    pub fn head(&self) -> Option<&T> {
+        self.node(self.head)?;
+
+        Some(self.item(self.head))
+    }
+
+    #[allow(dead_code)]
+    pub fn head_index(&self) -> Option<DlIndex<T>> {
+        self.handle(self.head)
+    }
+
+    #[allow(dead_code)]
+    pub fn tail_index(&self) -> Option<DlIndex<T>> {
+        self.handle(self.tail)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_back(&mut self, item: T) -> DlIndex<T> {
+        let tail = self.tail;
+
+        self.link_between(tail, SENTINEL, item)
+    }
+
+    #[allow(dead_code)]
+    pub fn push_front(&mut self, item: T) -> DlIndex<T> {
+        let head = self.head;
+
+        self.link_between(SENTINEL, head, item)
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, value: &T) -> bool {
+        self.iter().any(|e| e == value)
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, index: DlIndex<T>) -> Option<&T> {
+        self.try_get(index).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn try_get(&self, index: DlIndex<T>) -> Result<&T, DlError> {
+        self.check(index)?;
+
+        Ok(self.item(index.index))
+    }
+
+    #[allow(dead_code)]
+    pub fn get_mut(&mut self, index: DlIndex<T>) -> Option<&mut T> {
+        self.check(index).ok()?;
+
+        Some(self.item_mut(index.index))
+    }
+
+    /// Replaces the item at `index`, returning the old one. The handle stays
+    /// valid.
+    #[allow(dead_code)]
+    pub fn replace(&mut self, index: DlIndex<T>, item: T) -> Result<T, DlError> {
+        self.check(index)?;
+
+        Ok(core::mem::replace(self.item_mut(index.index), item))
+    }
+
+    /// Exchanges the items stored at `a` and `b` without relinking, so each
+    /// handle now addresses the other's former item at its own position.
+    #[allow(dead_code)]
+    pub fn swap_values(&mut self, a: DlIndex<T>, b: DlIndex<T>) -> Result<(), DlError> {
+        self.check(a)?;
+        self.check(b)?;
+
+        self.items.swap(a.index, b.index);
+
+        Ok(())
+    }
+
+    /// Runs `f` on the item at `index` in place, returning its result.
+    #[allow(dead_code)]
+    pub fn update<R, F>(&mut self, index: DlIndex<T>, f: F) -> Result<R, DlError>
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        self.check(index)?;
+
+        Ok(f(self.item_mut(index.index)))
+    }
+
+    /// Moves `index` to the head of the list. O(1).
+    #[allow(dead_code)]
+    pub fn move_to_front(&mut self, index: DlIndex<T>) -> Result<(), DlError> {
+        self.check(index)?;
+
+        self.unlink(index.index);
+
+        let head = self.head;
+        self.set_links(index.index, SENTINEL, head);
+
+        Ok(())
+    }
+
+    /// Moves `index` to the tail of the list. O(1).
+    #[allow(dead_code)]
+    pub fn move_to_back(&mut self, index: DlIndex<T>) -> Result<(), DlError> {
+        self.check(index)?;
+
+        self.unlink(index.index);
+
+        let tail = self.tail;
+        self.set_links(index.index, tail, SENTINEL);
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub fn contains_index(&self, index: DlIndex<T>) -> bool {
+        self.check(index).is_ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn next_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        let next = self.check(index).ok()?.next;
+
+        self.handle(next)
+    }
+
+    #[allow(dead_code)]
+    pub fn prev_index(&self, index: DlIndex<T>) -> Option<DlIndex<T>> {
+        let prev = self.check(index).ok()?.prev;
+
+        self.handle(prev)
+    }
+
+    #[allow(dead_code)]
+    pub fn remove(&mut self, index: DlIndex<T>) -> Option<T> {
+        self.try_remove(index).ok()
+    }
+
+    #[allow(dead_code)]
+    pub fn try_remove(&mut self, index: DlIndex<T>) -> Result<T, DlError> {
+        self.check(index)?;
+
+        Ok(self.remove_slot(index.index))
+    }
+
+    #[allow(dead_code)]
+    pub fn pop_front(&mut self) -> Option<T> {
+        let head = self.head;
+
+        self.node(head)?;
+
+        Some(self.remove_slot(head))
+    }
+
+    #[allow(dead_code)]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        let mut next = self.head;
+
+        core::iter::from_fn(move || {
+            let index = next;
+
+            next = self.node(index)?.next;
+
+            Some(self.item(index))
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn index_of(&self, item: &T) -> Option<DlIndex<T>> {
+        let mut next = self.head;
+
+        while let Some(links) = self.node(next) {
+            if self.item(next) == item {
+                return self.handle(next);
+            }
+
+            next = links.next;
+        }
+
+        None
+    }
+
+    /// Returns the item at position `pos`, counting from the head.
+    ///
+    /// Walks from whichever end is closer: O(min(pos, len - pos)).
+    #[allow(dead_code)]
+    pub fn nth(&self, pos: usize) -> Option<&T> {
+        let index = self.slot_at(pos)?;
+
+        Some(self.item(index))
+    }
+
+    /// Returns the handle of the item at position `pos`.
+    ///
+    /// Walks from whichever end is closer: O(min(pos, len - pos)).
+    #[allow(dead_code)]
+    pub fn nth_index(&self, pos: usize) -> Option<DlIndex<T>> {
+        self.handle(self.slot_at(pos)?)
+    }
+
+    /// Returns the position of `index` counting from the head, or `None` if
+    /// the handle is stale.
+    #[allow(dead_code)]
+    pub fn position_of(&self, index: DlIndex<T>) -> Option<usize> {
+        self.check(index).ok()?;
+
+        Some(self.slot_position(index.index))
+    }
+
+    /// Inserts `item` so that it ends up at position `pos`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos > len`.
+    #[allow(dead_code)]
+    pub fn insert_at(&mut self, pos: usize, item: T) -> DlIndex<T> {
+        let len = self.len;
+
+        assert!(
+            pos <= len,
+            "insertion position (is {}) should be <= len (is {})",
+            pos,
+            len
+        );
+
+        let next = self.slot_at(pos).unwrap_or(SENTINEL);
+        let prev = self.prev_of(next);
+
+        self.link_between(prev, next, item)
+    }
+
+    /// Inserts `item` directly after `index`.
+    #[allow(dead_code)]
+    pub fn insert_after(&mut self, index: DlIndex<T>, item: T) -> Result<DlIndex<T>, DlError> {
+        self.check(index)?;
+
+        let next = self.next_of(index.index);
+
+        Ok(self.link_between(index.index, next, item))
+    }
+
+    /// Removes and returns the item at position `pos`.
+    #[allow(dead_code)]
+    pub fn remove_at(&mut self, pos: usize) -> Option<T> {
+        let index = self.slot_at(pos)?;
+
+        Some(self.remove_slot(index))
+    }
+
+    /// Drops every item and releases the storage. All handles become stale.
+    #[allow(dead_code)]
+    pub fn clear(&mut self) {
+        self.links = Vec::new();
+        self.items = Vec::new();
+        self.next_free = None;
+        self.head = SENTINEL;
+        self.tail = SENTINEL;
+
+        // As in `DlList::clear_keep_capacity`, so that no slot comes back
+        // with the generation of a handle issued before the clear.
+        self.rm_cnt += self.len;
+        self.len = 0;
+    }
+
+    /// Removes items from the tail until at most `len` are left.
+    #[allow(dead_code)]
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            let tail = self.tail;
+
+            self.remove_slot(tail);
+        }
+    }
+
+    /// Removes items from the head until at most `len` are left.
+    #[allow(dead_code)]
+    pub fn truncate_front(&mut self, len: usize) {
+        while self.len > len {
+            let head = self.head;
+
+            self.remove_slot(head);
+        }
+    }
+
+    /// Reverses the order of the list. Handles stay valid. O(n).
+    #[allow(dead_code)]
+    pub fn reverse(&mut self) {
+        self.reverse_links();
+    }
+
+    /// Rotates the list so that the item at position `n % len` becomes the
+    /// head.
+    #[allow(dead_code)]
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        if let Some(index) = self.slot_at(n % self.len) {
+            self.rotate_to_slot(index);
+        }
+    }
+
+    /// Rotates the list so that the last `n % len` items move to the front.
+    #[allow(dead_code)]
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
+
+        self.rotate_left(self.len - n % self.len);
+    }
+
+    /// Rotates the list so that `index` becomes the head. O(1).
+    #[allow(dead_code)]
+    pub fn rotate_to_front(&mut self, index: DlIndex<T>) -> Result<(), DlError> {
+        self.check(index)?;
+
+        self.rotate_to_slot(index.index);
+
+        Ok(())
+    }
+
+    /// Exchanges the positions of `a` and `b` by relinking. O(1).
+    #[allow(dead_code)]
+    pub fn swap(&mut self, a: DlIndex<T>, b: DlIndex<T>) -> Result<(), DlError> {
+        self.check(a)?;
+        self.check(b)?;
+
+        self.swap_slots(a.index, b.index);
+
+        Ok(())
+    }
+
+    fn link_between(&mut self, prev: usize, next: usize, item: T) -> DlIndex<T> {
+        let rm_cnt = self.rm_cnt;
+
+        let index = self.alloc(item, Links { next, prev, rm_cnt });
+
+        self.relink(index);
+        self.len += 1;
+
+        DlIndex::new(index, rm_cnt, self.list_id.0)
+    }
+
+    fn remove_slot(&mut self, index: usize) -> T {
+        self.unlink(index);
+
+        self.links[index] = DlEntry::Free {
+            next_free: self.next_free,
+        };
+
+        self.next_free = Some(index);
+
+        self.rm_cnt += 1;
+        self.len -= 1;
+
+        match self.items[index].take() {
+            None => panic!("list corrupted!"),
+            Some(item) => item,
+        }
+    }
+
+    fn alloc(&mut self, item: T, links: Links) -> usize {
+        if let Some(index) = self.next_free {
+            match self.links[index] {
+                DlEntry::Occupied { .. } => panic!("list corrupted!"),
+                DlEntry::Free { next_free } => self.next_free = next_free,
+            }
+
+            self.links[index] = DlEntry::Occupied(links);
+            self.items[index] = Some(item);
+
+            index
+        } else {
+            self.links.push(DlEntry::Occupied(links));
+            self.items.push(Some(item));
+
+            self.links.len() - 1
+        }
+    }
+
+    fn item(&self, index: usize) -> &T {
+        match &self.items[index] {
+            None => panic!("list corrupted!"),
+            Some(item) => item,
+        }
+    }
+
+    fn item_mut(&mut self, index: usize) -> &mut T {
+        match &mut self.items[index] {
+            None => panic!("list corrupted!"),
+            Some(item) => item,
+        }
+    }
+}
+
+impl<T> Chain<T> for SoaDlList<T> {
+    type Node = Links;
+
+    fn slots(&self) -> &[DlEntry<Links>] {
+        &self.links
+    }
+
+    fn slots_mut(&mut self) -> &mut [DlEntry<Links>] {
+        &mut self.links
+    }
+
+    fn ends(&self) -> (usize, usize) {
+        (self.head, self.tail)
+    }
+
+    fn ends_mut(&mut self) -> (&mut usize, &mut usize) {
+        (&mut self.head, &mut self.tail)
+    }
+
+    fn count(&self) -> usize {
+        self.len
+    }
+
+    fn id(&self) -> ListId {
+        self.list_id.0
+    }
+}
+
+impl<T> core::ops::Index<DlIndex<T>> for SoaDlList<T>
+where
+    T: PartialEq,
+    T: core::fmt::Debug,
+{
+    type Output = T;
+
+    fn index(&self, index: DlIndex<T>) -> &Self::Output {
+        self.try_get(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_remove() {
+        let mut list = SoaDlList::new();
+
+        let one = list.push_back(1);
+        let two = list.push_back(2);
+        let zero = list.push_front(0);
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(list.head(), Some(&0));
+        assert_eq!(list.next_index(zero), Some(one));
+        assert_eq!(list.prev_index(two), Some(one));
+        assert_eq!(list.index_of(&2), Some(two));
+
+        assert_eq!(list.remove(one), Some(1));
+        assert_eq!(list.get(one), None);
+        assert_eq!(list.items[one.index], None);
+        assert_eq!(list.next_index(zero), Some(two));
+
+        let three = list.push_back(3);
+        assert_eq!(three.index, one.index);
+        assert_eq!(list.tail_index(), Some(three));
+
+        *list.get_mut(three).unwrap() += 1;
+        assert_eq!(list[three], 4);
+
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.len(), 2);
+        assert!(list.contains(&2));
+    }
+
+    #[test]
+    fn positions() {
+        let mut list = SoaDlList::new();
+
+        let one = list.push_back(1);
+        let three = list.push_back(3);
+        let zero = list.insert_at(0, 0);
+        let two = list.insert_after(one, 2).unwrap();
+
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        assert_eq!(list.nth(2), Some(&2));
+        assert_eq!(list.nth_index(3), Some(three));
+        assert_eq!(list.position_of(two), Some(2));
+
+        list.reverse();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3, 2, 1, 0]);
+
+        list.rotate_left(1);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![2, 1, 0, 3]);
+
+        list.rotate_right(1);
+        list.rotate_to_front(one).unwrap();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 0, 3, 2]);
+
+        list.swap(zero, two).unwrap();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 0]);
+
+        list.move_to_front(zero).unwrap();
+        list.move_to_back(one).unwrap();
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![0, 2, 3, 1]);
+
+        list.swap_values(zero, one).unwrap();
+        assert_eq!(list[zero], 1);
+        assert_eq!(list.replace(one, 10), Ok(0));
+        assert_eq!(list.update(two, |i| *i * 10), Ok(20));
+
+        assert_eq!(list.remove_at(1), Some(2));
+        assert_eq!(list.get(two), None);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![1, 3, 10]);
+
+        list.truncate_front(2);
+        list.truncate(1);
+        assert_eq!(list.iter().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(list.head_index(), Some(three));
+        assert_eq!(list.tail_index(), Some(three));
+
+        list.clear();
+        assert!(list.is_empty());
+        assert_eq!(list.get(three), None);
+        assert_ne!(list.push_back(4), three);
+    }
+
+    #[test]
+    fn pop_front() {
+        let mut list = SoaDlList::new();
+
+        list.push_back(1);
+        list.push_back(2);
+
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), None);
+
+        assert_eq!(
+            list.links,
+            vec![
+                DlEntry::Free { next_free: None },
+                DlEntry::Free { next_free: Some(0) },
+            ]
+        );
+        assert_eq!(list.head, SENTINEL);
+        assert_eq!(list.tail, SENTINEL);
+    }
+}
@@ -0,0 +1,11 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+// `#![no_std]` brings `core` into the crate root on its own.
+#[cfg(any(feature = "std", test))]
+extern crate core;
+
+extern crate alloc;
+
+mod dl_list;
+
+pub use dl_list::*;
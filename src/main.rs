@@ -1,4 +1,5 @@
-mod dl_list;
+extern crate dl_list;
+
 use dl_list::*;
 
 fn main() {